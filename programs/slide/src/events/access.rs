@@ -0,0 +1,17 @@
+use crate::state::Role;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AccessGranted {
+    pub expense_manager: Pubkey,
+    pub user: Pubkey,
+    pub role: Role,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccessRevoked {
+    pub expense_manager: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
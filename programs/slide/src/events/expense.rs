@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ExpenseManagerCreated {
+    pub expense_manager: Pubkey,
+    pub namespace: Option<Pubkey>,
+    pub name: String,
+    pub membership_token_mint: Pubkey,
+    pub payer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerInitialized {
+    pub expense_manager: Pubkey,
+    pub external_program_id: Pubkey,
+    pub realm: Option<Pubkey>,
+    pub governance_authority: Option<Pubkey>,
+    pub squad: Option<Pubkey>,
+    pub member: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerMetadataUpdated {
+    pub expense_manager: Pubkey,
+    pub display_name: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MembershipTokenMintUpdated {
+    pub expense_manager: Pubkey,
+    pub previous_mint: Pubkey,
+    pub membership_token_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerFunded {
+    pub expense_manager: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerWithdrawal {
+    pub expense_manager: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerClosed {
+    pub expense_manager: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerPaused {
+    pub expense_manager: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseManagerUnpaused {
+    pub expense_manager: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub expense_manager: Pubkey,
+    pub withdrawal_request: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawalRequestCancelled {
+    pub expense_manager: Pubkey,
+    pub withdrawal_request: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageCreated {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u32,
+    pub quantity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageUpdated {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub owner: Pubkey,
+    pub quantity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageSubmitted {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub owner: Pubkey,
+    pub payee: Pubkey,
+    pub quantity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageApproved {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub reviewer: Pubkey,
+    pub quantity: u64,
+    pub approved_amount: u64,
+    pub vesting: Option<VestingSchedule>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageDenied {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub reviewer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecurringExpenseCreated {
+    pub expense_manager: Pubkey,
+    pub recurring_expense: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub period: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecurringExpenseApproved {
+    pub expense_manager: Pubkey,
+    pub recurring_expense: Pubkey,
+    pub approved_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecurringExpensePaid {
+    pub expense_manager: Pubkey,
+    pub recurring_expense: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseAdvanceReconciliationSubmitted {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub owner: Pubkey,
+    pub actual_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseAdvanceReconciled {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub reviewer: Pubkey,
+    pub actual_amount: u64,
+    pub accepted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageEscalated {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackageExpired {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    // lamports returned to the manager
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpensePackagePaid {
+    pub expense_manager: Pubkey,
+    pub expense_package: Pubkey,
+    pub payee: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,7 @@
+pub mod access;
+pub mod expense;
+pub mod proposal;
+
+pub use access::*;
+pub use expense::*;
+pub use proposal::*;
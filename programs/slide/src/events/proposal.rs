@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ProposalExecuted {
+    pub expense_manager: Pubkey,
+    pub proposal: Pubkey,
+    pub executed_by: Pubkey,
+    pub timestamp: i64,
+}
@@ -4,7 +4,17 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct InitializeUser<'info> {
-    #[account(init, seeds = [b"user-data", user.key().as_ref()], bump, payer = user, space = UserData::MAX_SIZE + 8)]
+    // user data already exists if the user created an expense package before registering,
+    // in which case only the profile is filled in and the package nonce is kept
+    #[account(
+        init_if_needed,
+        seeds = [b"user-data", user.key().as_ref()],
+        bump,
+        payer = user,
+        space = UserData::MAX_SIZE + 8,
+        constraint = !user_data.has_profile() @ SlideError::UserAlreadyInitialized,
+        constraint = user_data.version == UserData::VERSION || user_data.user == Pubkey::default() @ SlideError::AccountNotMigrated
+    )]
     pub user_data: Account<'info, UserData>,
     #[account(mut)]
     pub user: Signer<'info>,
@@ -14,13 +24,96 @@ pub struct InitializeUser<'info> {
 #[derive(Accounts)]
 #[instruction(name: String)]
 pub struct CreateExpenseManager<'info> {
-    #[account(init, seeds = [b"expense-manager", name.as_bytes()], bump, payer = payer, space = ExpenseManager::MAX_SIZE + 8)]
+    #[account(init, seeds = [b"expense-manager", payer.key().as_ref(), name.as_bytes()], bump, payer = payer, space = ExpenseManager::MAX_SIZE + 8)]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(init, seeds = [b"audit-log", expense_manager.key().as_ref()], bump, payer = payer, space = AuditLog::SIZE + 8)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(init, seeds = [b"manager-stats", expense_manager.key().as_ref()], bump, payer = payer, space = ExpenseManagerStats::MAX_SIZE + 8)]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Managers created before the audit log existed can have one created by anyone
+#[derive(Accounts)]
+pub struct InitializeAuditLog<'info> {
+    #[account(init, seeds = [b"audit-log", expense_manager.key().as_ref()], bump, payer = payer, space = AuditLog::SIZE + 8)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Likewise for managers created before stats were tracked
+#[derive(Accounts)]
+pub struct InitializeManagerStats<'info> {
+    #[account(init, seeds = [b"manager-stats", expense_manager.key().as_ref()], bump, payer = payer, space = ExpenseManagerStats::MAX_SIZE + 8)]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
     pub expense_manager: Account<'info, ExpenseManager>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+// Metadata is optional, anyone can create an empty one for governance to fill in
+#[derive(Accounts)]
+pub struct InitializeManagerMetadata<'info> {
+    #[account(init, seeds = [b"manager-metadata", expense_manager.key().as_ref()], bump, payer = payer, space = ExpenseManagerMetadata::MAX_SIZE + 8)]
+    pub manager_metadata: Account<'info, ExpenseManagerMetadata>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless, anyone can pay to bring an account up to the current layout
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: deserialized as the expected account type by the migrate_* instruction
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundExpenseManager<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 // TODO: there should be an instruction to fully close the account to get
 //   rent back
 #[derive(Accounts)]
@@ -30,9 +123,524 @@ pub struct WithdrawFromExpensePackage<'info> {
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
         bump = expense_package.bump,
-        constraint = expense_package.state == ExpensePackageState::Approved @ SlideError::PackageNotApproved
+        constraint = expense_package.state == ExpensePackageState::Approved @ SlideError::PackageNotApproved,
+        has_one = owner,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    /// CHECK: Any address can receive a reimbursement, it only needs to match the package
+    #[account(
+        mut,
+        constraint = payee.key() == expense_package.payout_address() @ SlideError::PayeeMismatch
+    )]
+    pub payee: AccountInfo<'info>,
+    /// CHECK: Must be the manager's squad if it has one (checked in the handler), otherwise unused
+    pub squad: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+// Vendors will not sign a withdrawal, so reviewers can push approved funds to the payee
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct PayExpensePackage<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Approved @ SlideError::PackageNotApproved,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    /// CHECK: Any address can receive a reimbursement, it only needs to match the package
+    #[account(
+        mut,
+        constraint = payee.key() == expense_package.payout_address() @ SlideError::PayeeMismatch
+    )]
+    pub payee: AccountInfo<'info>,
+    /// CHECK: Must be the manager's squad if it has one (checked in the handler), otherwise unused
+    pub squad: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+// Line items only touch the package itself, so (like withdrawal) these do not
+// re-check DAO membership; that was verified when the package was created
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct EditExpenseLineItems<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
+        has_one = owner,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct EditExpenseAttachments<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.can_edit_attachments() @ SlideError::PackageFrozen,
+        has_one = owner,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless once the timelock has passed, the funds can only go to the recorded destination
+#[derive(Accounts)]
+pub struct ExecuteWithdrawalRequest<'info> {
+    #[account(
+        mut,
+        has_one = expense_manager,
+        has_one = destination,
+        has_one = payer,
+        close = payer
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    /// CHECK: Treasury recorded on the request
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+    /// CHECK: Receives the request's rent, recorded on the request
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawalRequest<'info> {
+    #[account(
+        mut,
+        has_one = expense_manager,
+        has_one = payer,
+        close = payer
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_manage() @ SlideError::UserCannotManageExpenseManager,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    /// CHECK: Receives the request's rent, recorded on the request
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+}
+
+// Permissionless, anyone can lapse packages which are past the manager's deadlines
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct CrankExpensePackage<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    pub cranker: Signer<'info>,
+}
+
+// Templates do nothing until approved, so anyone can propose one for themselves
+#[derive(Accounts)]
+#[instruction(nonce: u32, name: String, amount: u64, period: u32, start: i64, end: Option<i64>, cap: Option<u64>, category: Option<u8>)]
+pub struct CreateRecurringExpense<'info> {
+    #[account(
+        init,
+        seeds = [b"recurring-expense", expense_manager.key().as_ref(), owner.key().as_ref(), &nonce.to_le_bytes()],
+        bump,
+        payer = owner,
+        space = RecurringExpense::MAX_SIZE + 8
+    )]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.has_category(category) @ SlideError::CategoryNotFound,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRecurringExpense<'info> {
+    #[account(
+        mut,
+        has_one = expense_manager,
+        constraint = recurring_expense.approved_by.is_none() @ SlideError::RecurringExpenseAlreadyApproved
+    )]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    #[account(
+        constraint = authority.key() != recurring_expense.owner @ SlideError::UserCannotApproveOrDenyOwnExpense
+    )]
+    pub authority: Signer<'info>,
+}
+
+// Permissionless, each call pays out one period once it falls due
+#[derive(Accounts)]
+pub struct PayRecurringExpense<'info> {
+    #[account(
+        mut,
+        has_one = expense_manager,
+        has_one = payee,
+        constraint = recurring_expense.approved_by.is_some() @ SlideError::RecurringExpenseNotApproved
+    )]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    /// CHECK: Any address can receive a recurring payout, it only needs to match the template
+    #[account(mut)]
+    pub payee: AccountInfo<'info>,
+    /// CHECK: Must be the manager's squad if it has one (checked in the handler), otherwise unused
+    pub squad: UncheckedAccount<'info>,
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRecurringExpense<'info> {
+    #[account(mut, has_one = owner, close = owner)]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct SetExpenseAdvance<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
+        has_one = owner,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    pub owner: Signer<'info>,
+}
+
+// Any refund owed to the manager is escrowed in the package until a reviewer signs off
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct ReconcileExpenseAdvance<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.advance && expense_package.state == ExpensePackageState::Paid @ SlideError::PackageNotAdvance,
+        has_one = owner,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.require_attachments || !expense_package.attachments.is_empty() @ SlideError::PackageMissingAttachments,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u32)]
+pub struct ReviewExpenseAdvance<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Reconciling @ SlideError::PackageNotReconciling,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_package: Account<'info, ExpensePackage>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    /// CHECK: Receives any top-up, it only needs to match the package
+    #[account(
+        mut,
+        constraint = payee.key() == expense_package.payout_address() @ SlideError::PayeeMismatch
+    )]
+    pub payee: AccountInfo<'info>,
+    /// CHECK: Receives the escrowed refund back if the reconciliation is rejected
+    #[account(
+        mut,
+        constraint = owner.key() == expense_package.owner @ SlideError::PackageOwnershipMismatch
+    )]
+    pub owner: AccountInfo<'info>,
+    #[account(
+        constraint = authority.key() != expense_package.owner @ SlideError::UserCannotApproveOrDenyOwnExpense
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExpenseManagerConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_manage() @ SlideError::UserCannotManageExpenseManager,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    pub authority: Signer<'info>,
+}
+
+// Permissionless, a lower timelock can only be applied once the current one has passed
+#[derive(Accounts)]
+pub struct ApplyWithdrawalTimelock<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+}
+
+#[derive(Accounts)]
+pub struct EditExpenseCategories<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_manage() @ SlideError::UserCannotManageExpenseManager,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ExpensePackages and their payees are passed through remaining_accounts
+#[derive(Accounts)]
+pub struct PayExpensePackages<'info> {
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    /// CHECK: Must be the manager's squad if it has one (checked in the handler), otherwise unused
+    pub squad: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
 }
@@ -1,11 +1,17 @@
 use crate::state::*;
 use crate::utils::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
 
 #[derive(Accounts)]
 #[instruction(realm: Pubkey, governance_type: GovernanceType, external_program_id: Pubkey)]
 pub struct SPLGovInitializeExpenseManager<'info> {
-    #[account(mut, seeds = [b"expense-manager", expense_manager.name.as_bytes()], bump = expense_manager.bump)]
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
     pub expense_manager: Account<'info, ExpenseManager>,
     #[account(
         seeds = [governance_type.seed_prefix().as_bytes(), realm.as_ref(), governance_authority.governed_account.as_ref()],
@@ -26,6 +32,36 @@ pub struct SPLGovInitializeExpenseManager<'info> {
     pub member: Signer<'info>,
 }
 
+// Creates and initializes a manager in one step, with its name scoped to the realm
+#[derive(Accounts)]
+#[instruction(realm: Pubkey, governance_type: GovernanceType, external_program_id: Pubkey, name: String, membership_token_mint: Pubkey)]
+pub struct SPLGovCreateExpenseManager<'info> {
+    #[account(init, seeds = [b"expense-manager", realm.as_ref(), name.as_bytes()], bump, payer = member, space = ExpenseManager::MAX_SIZE + 8)]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(init, seeds = [b"audit-log", expense_manager.key().as_ref()], bump, payer = member, space = AuditLog::SIZE + 8)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(init, seeds = [b"manager-stats", expense_manager.key().as_ref()], bump, payer = member, space = ExpenseManagerStats::MAX_SIZE + 8)]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [governance_type.seed_prefix().as_bytes(), realm.as_ref(), governance_authority.governed_account.as_ref()],
+        bump,
+        seeds::program = external_program_id,
+        owner = external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+    #[account(
+        seeds = [b"governance", realm.as_ref(), membership_token_mint.as_ref(), member.key().as_ref()],
+        bump,
+        seeds::program = external_program_id,
+        constraint = token_owner_record.governing_token_deposit_amount > 0 @ SlideError::UserIsNotDAOMember,
+        owner = external_program_id
+    )]
+    pub token_owner_record: Account<'info, TokenOwnerRecord>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(realm: Pubkey, user: Pubkey, role: Role)]
 pub struct SPLGovCreateAccessRecord<'info> {
@@ -38,11 +74,19 @@ pub struct SPLGovCreateAccessRecord<'info> {
     )]
     pub access_record: Account<'info, AccessRecord>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
     #[account(
         signer,
         seeds = [
@@ -70,11 +114,27 @@ pub struct SPLGovCreateAccessRecord<'info> {
 pub struct SPLGovWithdrawFromExpenseManager<'info> {
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.withdrawal_timelock.is_none() @ SlideError::WithdrawalTimelocked,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         signer,
         seeds = [
@@ -97,8 +157,224 @@ pub struct SPLGovWithdrawFromExpenseManager<'info> {
     pub native_treasury: AccountInfo<'info>,
 }
 
+// The native treasury pays for the request and is refunded once it is executed or cancelled
 #[derive(Accounts)]
-#[instruction(realm: Pubkey, nonce: u32, name: String, description: String, quantity: u64)]
+#[instruction(realm: Pubkey, nonce: u32)]
+pub struct SPLGovQueueWithdrawal<'info> {
+    #[account(
+        init,
+        seeds = [b"withdrawal-request", expense_manager.key().as_ref(), &nonce.to_le_bytes()],
+        bump,
+        payer = native_treasury,
+        space = WithdrawalRequest::MAX_SIZE + 8
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+    /// CHECK: The seeds constraint is sufficient here, governance signs for the treasury
+    #[account(
+        mut,
+        signer,
+        seeds = [b"native-treasury", governance_authority.key().as_ref()],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub native_treasury: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovCloseExpenseManager<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        close = native_treasury,
+        constraint = expense_manager.withdrawal_timelock.is_none() @ SlideError::WithdrawalTimelocked,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump,
+        close = native_treasury
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        close = native_treasury,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    /// CHECK: Closed along with the manager if it was ever initialized
+    #[account(
+        mut,
+        seeds = [b"manager-metadata", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub manager_metadata: UncheckedAccount<'info>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+    /// CHECK: The seeds constraint is sufficient here, and the treasury does not need to sign (governance already signed)
+    #[account(
+        mut,
+        seeds = [b"native-treasury", governance_authority.key().as_ref()],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub native_treasury: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Changes to the manager itself which only governance can make, such as unpausing it
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovUpdateExpenseManager<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+}
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovApproveRecurringExpense<'info> {
+    #[account(
+        mut,
+        has_one = expense_manager,
+        constraint = recurring_expense.approved_by.is_none() @ SlideError::RecurringExpenseAlreadyApproved
+    )]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+}
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovUpdateManagerMetadata<'info> {
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"manager-metadata", expense_manager.key().as_ref()],
+        bump = manager_metadata.bump
+    )]
+    pub manager_metadata: Account<'info, ExpenseManagerMetadata>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+}
+
+// Only a governance signer (i.e. an executed proposal) can change who counts as a member
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovSetMembershipTokenMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        signer,
+        seeds = [
+            expense_manager.governance_type.as_ref().unwrap().seed_prefix().as_bytes(),
+            realm.as_ref(),
+            governance_authority.governed_account.as_ref()
+        ],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub governance_authority: Account<'info, Governance>,
+}
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey, nonce: u32, name: String, description: String, quantity: u64, category: Option<u8>)]
 pub struct SPLGovCreateExpensePackage<'info> {
     #[account(
         init,
@@ -108,14 +384,34 @@ pub struct SPLGovCreateExpensePackage<'info> {
         space = ExpensePackage::MAX_SIZE + 8
     )]
     pub expense_package: Account<'info, ExpensePackage>,
+    // nonces may skip ahead so owners can step over packages created under the old
+    // manager-wide counter; user data is created here if the owner never initialized it
     #[account(
-        mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        init_if_needed,
+        seeds = [b"user-data", owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = UserData::MAX_SIZE + 8,
+        constraint = nonce >= user_data.expense_package_nonce @ SlideError::IncorrectNonce,
+        constraint = user_data.version == UserData::VERSION || user_data.user == Pubkey::default() @ SlideError::AccountNotMigrated
+    )]
+    pub user_data: Account<'info, UserData>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = nonce == expense_manager.expense_package_nonce @ SlideError::IncorrectNonce,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = expense_manager.has_category(category) @ SlideError::CategoryNotFound,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"governance", realm.as_ref(), expense_manager.membership_token_mint.as_ref(), owner.key().as_ref()],
         bump,
@@ -138,13 +434,15 @@ pub struct SPLGovUpdateExpensePackage<'info> {
         bump = expense_package.bump,
         constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
         has_one = owner,
-        has_one = expense_manager
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
     #[account(
@@ -160,7 +458,7 @@ pub struct SPLGovUpdateExpensePackage<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(realm: Pubkey, nonce: u32)]
+#[instruction(realm: Pubkey, nonce: u32, payee: Option<Pubkey>)]
 pub struct SPLGovSubmitExpensePackage<'info> {
     #[account(
         mut,
@@ -169,15 +467,32 @@ pub struct SPLGovSubmitExpensePackage<'info> {
         constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
         constraint = expense_package.quantity > 0 && !expense_package.name.is_empty() @ SlideError::PackageMissingInfo,
         has_one = owner,
-        has_one = expense_manager
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = !expense_manager.require_attachments || !expense_package.attachments.is_empty() @ SlideError::PackageMissingAttachments,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"governance", realm.as_ref(), expense_manager.membership_token_mint.as_ref(), owner.key().as_ref()],
         bump,
@@ -196,16 +511,34 @@ pub struct SPLGovApproveExpensePackage<'info> {
     #[account(
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
-        bump = expense_package.bump
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Pending @ SlideError::PackageNotPending,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"governance", realm.as_ref(), expense_manager.membership_token_mint.as_ref(), authority.key().as_ref()],
         bump,
@@ -217,9 +550,16 @@ pub struct SPLGovApproveExpensePackage<'info> {
     #[account(
         seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
         bump = access_record.bump,
-        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
     )]
     pub access_record: Account<'info, AccessRecord>,
+    /// CHECK: Only receives funds when the manager pays out directly on approval
+    #[account(
+        mut,
+        constraint = payee.key() == expense_package.payout_address() @ SlideError::PayeeMismatch
+    )]
+    pub payee: AccountInfo<'info>,
     #[account(
         mut,
         constraint = authority.key() != expense_package.owner @ SlideError::UserCannotApproveOrDenyOwnExpense
@@ -233,15 +573,32 @@ pub struct SPLGovDenyExpensePackage<'info> {
     #[account(
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
-        bump = expense_package.bump
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Pending @ SlideError::PackageNotPending,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"governance", realm.as_ref(), expense_manager.membership_token_mint.as_ref(), authority.key().as_ref()],
         bump,
@@ -253,7 +610,8 @@ pub struct SPLGovDenyExpensePackage<'info> {
     #[account(
         seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
         bump = access_record.bump,
-        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
     )]
     pub access_record: Account<'info, AccessRecord>,
     #[account(
@@ -263,6 +621,50 @@ pub struct SPLGovDenyExpensePackage<'info> {
     pub authority: Signer<'info>,
 }
 
+// ExpensePackages (and their payees, when approving) are passed through remaining_accounts
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct SPLGovReviewExpensePackages<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(realm) == expense_manager.realm @ SlideError::SPLGovRealmMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"governance", realm.as_ref(), expense_manager.membership_token_mint.as_ref(), authority.key().as_ref()],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = token_owner_record.governing_token_deposit_amount > 0 @ SlideError::UserIsNotDAOMember,
+        owner = expense_manager.external_program_id
+    )]
+    pub token_owner_record: Account<'info, TokenOwnerRecord>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 // #[derive(Accounts)]
 // #[instruction()]
 // pub struct SPLGovCreateAccessProposal<'info> {}
@@ -1,15 +1,16 @@
 use crate::state::*;
 use crate::utils::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 
 #[derive(Accounts)]
 #[instruction(external_program_id: Pubkey)]
 pub struct SquadsInitializeExpenseManager<'info> {
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
-        bump = expense_manager.bump
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
     #[account(
@@ -27,12 +28,54 @@ pub struct SquadsInitializeExpenseManager<'info> {
         owner = external_program_id
     )]
     pub squad: Box<Account<'info, Squad>>,
+    /// CHECK: Only exists if a manager at this address was closed before, see ProposalWatermark
+    #[account(
+        seeds = [b"proposal-watermark", expense_manager.key().as_ref(), squad.key().as_ref()],
+        bump
+    )]
+    pub proposal_watermark: UncheckedAccount<'info>,
     #[account(mut)]
     pub member: Signer<'info>,
 }
 
+// Creates and initializes a manager in one step, with its name scoped to the squad
 #[derive(Accounts)]
-#[instruction(nonce: u32, name: String, description: String, quantity: u64)]
+#[instruction(external_program_id: Pubkey, name: String)]
+pub struct SquadsCreateExpenseManager<'info> {
+    #[account(init, seeds = [b"expense-manager", squad.key().as_ref(), name.as_bytes()], bump, payer = member, space = ExpenseManager::MAX_SIZE + 8)]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(init, seeds = [b"audit-log", expense_manager.key().as_ref()], bump, payer = member, space = AuditLog::SIZE + 8)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(init, seeds = [b"manager-stats", expense_manager.key().as_ref()], bump, payer = member, space = ExpenseManagerStats::MAX_SIZE + 8)]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [member.key().as_ref(), squad.key().as_ref(), b"!memberequity"],
+        bump,
+        seeds::program = external_program_id,
+        constraint = member_equity.mint == squad.mint_address @ SlideError::SquadMintMismatch,
+        constraint = member_equity.amount > 0 @ SlideError::UserIsNotDAOMember
+    )]
+    pub member_equity: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = external_program_id,
+        owner = external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    /// CHECK: Only exists if a manager at this address was closed before, see ProposalWatermark
+    #[account(
+        seeds = [b"proposal-watermark", expense_manager.key().as_ref(), squad.key().as_ref()],
+        bump
+    )]
+    pub proposal_watermark: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u32, name: String, description: String, quantity: u64, category: Option<u8>)]
 pub struct SquadsCreateExpensePackage<'info> {
     #[account(
         init,
@@ -42,14 +85,34 @@ pub struct SquadsCreateExpensePackage<'info> {
         space = ExpensePackage::MAX_SIZE + 8
     )]
     pub expense_package: Account<'info, ExpensePackage>,
+    // nonces may skip ahead so owners can step over packages created under the old
+    // manager-wide counter; user data is created here if the owner never initialized it
     #[account(
-        mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        init_if_needed,
+        seeds = [b"user-data", owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = UserData::MAX_SIZE + 8,
+        constraint = nonce >= user_data.expense_package_nonce @ SlideError::IncorrectNonce,
+        constraint = user_data.version == UserData::VERSION || user_data.user == Pubkey::default() @ SlideError::AccountNotMigrated
+    )]
+    pub user_data: Account<'info, UserData>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = nonce == expense_manager.expense_package_nonce @ SlideError::IncorrectNonce,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = expense_manager.has_category(category) @ SlideError::CategoryNotFound,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [owner.key().as_ref(), squad.key().as_ref(), b"!memberequity"],
         bump,
@@ -62,7 +125,8 @@ pub struct SquadsCreateExpensePackage<'info> {
         seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
         bump,
         seeds::program = expense_manager.external_program_id,
-        owner = expense_manager.external_program_id
+        owner = expense_manager.external_program_id,
+        constraint = !squad.emergency_lock @ SlideError::SquadEmergencyLock
     )]
     pub squad: Box<Account<'info, Squad>>,
     #[account(mut)]
@@ -77,14 +141,17 @@ pub struct SquadsUpdateExpensePackage<'info> {
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
         bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
         has_one = owner,
-        has_one = expense_manager
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
     #[account(
@@ -107,7 +174,7 @@ pub struct SquadsUpdateExpensePackage<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(nonce: u32)]
+#[instruction(nonce: u32, payee: Option<Pubkey>)]
 pub struct SquadsSubmitExpensePackage<'info> {
     #[account(
         mut,
@@ -116,15 +183,32 @@ pub struct SquadsSubmitExpensePackage<'info> {
         constraint = expense_package.state == ExpensePackageState::Created @ SlideError::PackageFrozen,
         constraint = expense_package.quantity > 0 && !expense_package.name.is_empty() @ SlideError::PackageMissingInfo,
         has_one = owner,
-        has_one = expense_manager
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = !expense_manager.require_attachments || !expense_package.attachments.is_empty() @ SlideError::PackageMissingAttachments,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [owner.key().as_ref(), squad.key().as_ref(), b"!memberequity"],
         bump,
@@ -137,7 +221,8 @@ pub struct SquadsSubmitExpensePackage<'info> {
         seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
         bump,
         seeds::program = expense_manager.external_program_id,
-        owner = expense_manager.external_program_id
+        owner = expense_manager.external_program_id,
+        constraint = !squad.emergency_lock @ SlideError::SquadEmergencyLock
     )]
     pub squad: Box<Account<'info, Squad>>,
     #[account(mut)]
@@ -151,6 +236,7 @@ pub struct SquadsExecuteAccessProposal<'info> {
         bump,
         seeds::program = expense_manager.external_program_id,
         constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
         owner = expense_manager.external_program_id
     )]
     pub proposal: Box<Account<'info, Proposal>>,
@@ -171,11 +257,19 @@ pub struct SquadsExecuteAccessProposal<'info> {
     )]
     pub access_record: Account<'info, AccessRecord>,
     #[account(
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
     #[account(
         seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
         bump,
@@ -196,6 +290,350 @@ pub struct SquadsExecuteAccessProposal<'info> {
     pub signer: Signer<'info>,
 }
 
+// Squads membership always follows the squad mint, so only display metadata
+// can be changed through a proposal here
+#[derive(Accounts)]
+pub struct SquadsExecuteMetadataProposal<'info> {
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal.proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
+        owner = expense_manager.external_program_id
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalExecution::MAX_SIZE + 8
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"manager-metadata", expense_manager.key().as_ref()],
+        bump = manager_metadata.bump
+    )]
+    pub manager_metadata: Account<'info, ExpenseManagerMetadata>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadmint"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+// Proposals which only change the manager itself, such as unpausing it
+#[derive(Accounts)]
+pub struct SquadsExecuteManagerProposal<'info> {
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal.proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
+        owner = expense_manager.external_program_id
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalExecution::MAX_SIZE + 8
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadmint"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SquadsExecuteRecurringExpenseProposal<'info> {
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal.proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
+        owner = expense_manager.external_program_id
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalExecution::MAX_SIZE + 8
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    #[account(
+        mut,
+        has_one = expense_manager,
+        constraint = recurring_expense.approved_by.is_none() @ SlideError::RecurringExpenseAlreadyApproved
+    )]
+    pub recurring_expense: Account<'info, RecurringExpense>,
+    #[account(
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadmint"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SquadsExecuteCloseProposal<'info> {
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal.proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
+        owner = expense_manager.external_program_id
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalExecution::MAX_SIZE + 8
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        close = squad_treasury,
+        constraint = expense_manager.withdrawal_timelock.is_none() @ SlideError::WithdrawalTimelocked,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump,
+        close = squad_treasury
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        close = squad_treasury,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    /// CHECK: Closed along with the manager if it was ever initialized
+    #[account(
+        mut,
+        seeds = [b"manager-metadata", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub manager_metadata: UncheckedAccount<'info>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadmint"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [squad.key().as_ref(), b"!squadsol"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_treasury: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [b"proposal-watermark", expense_manager.key().as_ref(), squad.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalWatermark::MAX_SIZE + 8
+    )]
+    pub proposal_watermark: Account<'info, ProposalWatermark>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+// Permissionless, the rent can only go back to whoever paid for the record. Proposals
+// between the watermark and this one are passed through remaining_accounts
+#[derive(Accounts)]
+#[instruction(proposal_index: u32)]
+pub struct SquadsCloseProposalExecution<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        close = rent_receiver,
+        constraint = proposal_execution.version == ProposalExecution::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    /// CHECK: The proposal may already be closed, so only its address is checked
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub proposal: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadsol"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_treasury: SystemAccount<'info>,
+    /// CHECK: Records which predate executed_by refund the squad treasury
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == proposal_execution.executed_by.unwrap_or_else(|| squad_treasury.key()) @ SlideError::RentReceiverMismatch
+    )]
+    pub rent_receiver: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SquadsQueueWithdrawalProposal<'info> {
+    #[account(
+        seeds = [squad.key().as_ref(), &proposal.proposal_index.to_le_bytes(), b"!proposal"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
+        owner = expense_manager.external_program_id
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init,
+        seeds = [b"proposal-execution", expense_manager.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = signer,
+        space = ProposalExecution::MAX_SIZE + 8
+    )]
+    pub proposal_execution: Account<'info, ProposalExecution>,
+    #[account(
+        init,
+        seeds = [b"withdrawal-request", expense_manager.key().as_ref(), &proposal.proposal_index.to_le_bytes()],
+        bump,
+        payer = signer,
+        space = WithdrawalRequest::MAX_SIZE + 8
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id,
+        constraint = !squad.emergency_lock @ SlideError::SquadEmergencyLock
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadmint"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [squad.key().as_ref(), b"!squadsol"],
+        bump,
+        seeds::program = expense_manager.external_program_id
+    )]
+    pub squad_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
 // TODO: lamports withdrawn should be read from proposal
 //   also source and destination pubkeys should match
 #[derive(Accounts)]
@@ -205,6 +643,7 @@ pub struct SquadsExecuteWithdrawalProposal<'info> {
         bump,
         seeds::program = expense_manager.external_program_id,
         constraint = proposal.proposal_type == 0 @ SlideError::WrongProposalType,
+        constraint = proposal.proposal_index >= expense_manager.min_proposal_index @ SlideError::ProposalAlreadyExecuted,
         owner = expense_manager.external_program_id
     )]
     pub proposal: Box<Account<'info, Proposal>>,
@@ -218,16 +657,33 @@ pub struct SquadsExecuteWithdrawalProposal<'info> {
     pub proposal_execution: Account<'info, ProposalExecution>,
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.withdrawal_timelock.is_none() @ SlideError::WithdrawalTimelocked,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
         bump,
         seeds::program = expense_manager.external_program_id,
-        owner = expense_manager.external_program_id
+        owner = expense_manager.external_program_id,
+        constraint = !squad.emergency_lock @ SlideError::SquadEmergencyLock
     )]
     pub squad: Box<Account<'info, Squad>>,
     #[account(
@@ -254,20 +710,39 @@ pub struct SquadsApproveExpensePackage<'info> {
     #[account(
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
-        bump = expense_package.bump
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Pending @ SlideError::PackageNotPending,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = !expense_manager.paused @ SlideError::ExpenseManagerPaused,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
         bump = access_record.bump,
-        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
     )]
     pub access_record: Account<'info, AccessRecord>,
     #[account(
@@ -282,9 +757,16 @@ pub struct SquadsApproveExpensePackage<'info> {
         seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
         bump,
         seeds::program = expense_manager.external_program_id,
-        owner = expense_manager.external_program_id
+        owner = expense_manager.external_program_id,
+        constraint = !squad.emergency_lock @ SlideError::SquadEmergencyLock
     )]
     pub squad: Box<Account<'info, Squad>>,
+    /// CHECK: Only receives funds when the manager pays out directly on approval
+    #[account(
+        mut,
+        constraint = payee.key() == expense_package.payout_address() @ SlideError::PayeeMismatch
+    )]
+    pub payee: AccountInfo<'info>,
     #[account(
         mut,
         constraint = authority.key() != expense_package.owner @ SlideError::UserCannotApproveOrDenyOwnExpense
@@ -298,20 +780,38 @@ pub struct SquadsDenyExpensePackage<'info> {
     #[account(
         mut,
         seeds = [b"expense-package", expense_package.expense_manager.as_ref(), expense_package.owner.as_ref(), &nonce.to_le_bytes()],
-        bump = expense_package.bump
+        bump = expense_package.bump,
+        constraint = expense_package.state == ExpensePackageState::Pending @ SlideError::PackageNotPending,
+        has_one = expense_manager,
+        constraint = expense_package.version == ExpensePackage::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_package: Account<'info, ExpensePackage>,
     #[account(
         mut,
-        seeds = [b"expense-manager", expense_manager.name.as_bytes()],
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
         bump = expense_manager.bump,
-        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
     )]
     pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
     #[account(
         seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
         bump = access_record.bump,
-        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
     )]
     pub access_record: Account<'info, AccessRecord>,
     #[account(
@@ -336,6 +836,56 @@ pub struct SquadsDenyExpensePackage<'info> {
     pub authority: Signer<'info>,
 }
 
+// ExpensePackages (and their payees, when approving) are passed through remaining_accounts
+#[derive(Accounts)]
+pub struct SquadsReviewExpensePackages<'info> {
+    #[account(
+        mut,
+        seeds = [b"expense-manager", expense_manager.namespace_seed(), expense_manager.name.as_bytes()],
+        bump = expense_manager.bump,
+        constraint = Some(squad.key()) == expense_manager.squad @ SlideError::SquadMismatch,
+        constraint = expense_manager.version == ExpenseManager::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub expense_manager: Account<'info, ExpenseManager>,
+    #[account(
+        mut,
+        seeds = [b"audit-log", expense_manager.key().as_ref()],
+        bump
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [b"manager-stats", expense_manager.key().as_ref()],
+        bump = manager_stats.bump,
+        constraint = manager_stats.version == ExpenseManagerStats::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub manager_stats: Account<'info, ExpenseManagerStats>,
+    #[account(
+        seeds = [b"access-record", expense_manager.key().as_ref(), authority.key().as_ref()],
+        bump = access_record.bump,
+        constraint = access_record.role.can_approve_and_deny() @ SlideError::UserCannotApproveOrDenyExpenses,
+        constraint = access_record.version == AccessRecord::VERSION @ SlideError::AccountNotMigrated
+    )]
+    pub access_record: Account<'info, AccessRecord>,
+    #[account(
+        seeds = [authority.key().as_ref(), squad.key().as_ref(), b"!memberequity"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        constraint = member_equity.mint == squad.mint_address @ SlideError::SquadMintMismatch,
+        constraint = member_equity.amount > 0 @ SlideError::UserIsNotDAOMember
+    )]
+    pub member_equity: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [squad.admin.as_ref(), squad.random_id.as_bytes(), b"!squad"],
+        bump,
+        seeds::program = expense_manager.external_program_id,
+        owner = expense_manager.external_program_id
+    )]
+    pub squad: Box<Account<'info, Squad>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 // #[derive(Accounts)]
 // #[instruction()]
 // pub struct SquadsCreateAccessProposal<'info> {}
@@ -1,8 +1,11 @@
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use events::*;
 use instructions::*;
 use state::*;
 use utils::*;
@@ -14,9 +17,6 @@ declare_id!("SLfcUcri1hhkZpN5W3R4XBQW4Ka5u4vGsG7M5mWq9Xh");
 // localnet
 // declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-// TODO: out-of-space errors (on dynamic stuff like strings)
-//   does this need to be handled explicitly?
-
 #[program]
 pub mod slide {
     use super::*;
@@ -31,8 +31,10 @@ pub mod slide {
 
         user_data.username = username;
         user_data.real_name = real_name;
+        require!(user_data.validate(), SlideError::DataTooLarge);
         user_data.user = user.key();
-        user_data.bump = *ctx.bumps.get("user_data").unwrap();
+        user_data.version = UserData::VERSION;
+        user_data.bump = *ctx.bumps.get("user_data").ok_or(SlideError::BumpNotFound)?;
 
         Ok(())
     }
@@ -42,10 +44,187 @@ pub mod slide {
         membership_token_mint: Pubkey,
     ) -> Result<()> {
         let expense_manager = &mut ctx.accounts.expense_manager;
+        let payer = &ctx.accounts.payer;
 
         expense_manager.name = name;
+        require!(expense_manager.validate(), SlideError::DataTooLarge);
         expense_manager.membership_token_mint = membership_token_mint;
-        expense_manager.bump = *ctx.bumps.get("expense_manager").unwrap();
+        expense_manager.namespace = Some(payer.key());
+        expense_manager.version = ExpenseManager::VERSION;
+        expense_manager.bump = *ctx
+            .bumps
+            .get("expense_manager")
+            .ok_or(SlideError::BumpNotFound)?;
+
+        let mut audit_log = ctx.accounts.audit_log.load_init()?;
+        audit_log.expense_manager = expense_manager.key();
+        audit_log.bump = *ctx.bumps.get("audit_log").ok_or(SlideError::BumpNotFound)?;
+
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        manager_stats.expense_manager = expense_manager.key();
+        manager_stats.bump = *ctx
+            .bumps
+            .get("manager_stats")
+            .ok_or(SlideError::BumpNotFound)?;
+        manager_stats.version = ExpenseManagerStats::VERSION;
+        manager_stats.counts_all_packages = true;
+
+        emit!(ExpenseManagerCreated {
+            expense_manager: expense_manager.key(),
+            namespace: expense_manager.namespace,
+            name: expense_manager.name.clone(),
+            membership_token_mint,
+            payer: payer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn initialize_audit_log(ctx: Context<InitializeAuditLog>) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let mut audit_log = ctx.accounts.audit_log.load_init()?;
+
+        audit_log.expense_manager = expense_manager.key();
+        audit_log.bump = *ctx.bumps.get("audit_log").ok_or(SlideError::BumpNotFound)?;
+
+        Ok(())
+    }
+    pub fn initialize_manager_stats(ctx: Context<InitializeManagerStats>) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        manager_stats.expense_manager = expense_manager.key();
+        manager_stats.bump = *ctx
+            .bumps
+            .get("manager_stats")
+            .ok_or(SlideError::BumpNotFound)?;
+        manager_stats.version = ExpenseManagerStats::VERSION;
+
+        Ok(())
+    }
+    pub fn initialize_manager_metadata(ctx: Context<InitializeManagerMetadata>) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let manager_metadata = &mut ctx.accounts.manager_metadata;
+
+        manager_metadata.expense_manager = expense_manager.key();
+        manager_metadata.display_name = expense_manager.name.clone();
+        manager_metadata.version = ExpenseManagerMetadata::VERSION;
+        manager_metadata.bump = *ctx
+            .bumps
+            .get("manager_metadata")
+            .ok_or(SlideError::BumpNotFound)?;
+
+        Ok(())
+    }
+    pub fn migrate_user_data(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut user_data: Account<UserData> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            |_| UserData::MAX_SIZE + 8,
+        )?;
+
+        user_data.version = UserData::VERSION;
+        user_data.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn migrate_expense_manager(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut expense_manager: Account<ExpenseManager> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ExpenseManager::space,
+        )?;
+
+        expense_manager.version = ExpenseManager::VERSION;
+        expense_manager.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn migrate_expense_package(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut expense_package: Account<ExpensePackage> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ExpensePackage::space,
+        )?;
+
+        expense_package.version = ExpensePackage::VERSION;
+        expense_package.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn migrate_access_record(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut access_record: Account<AccessRecord> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            |_| AccessRecord::MAX_SIZE + 8,
+        )?;
+
+        access_record.version = AccessRecord::VERSION;
+        access_record.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn migrate_proposal_execution(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut proposal_execution: Account<ProposalExecution> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            |_| ProposalExecution::MAX_SIZE + 8,
+        )?;
+
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn migrate_manager_stats(ctx: Context<MigrateAccount>) -> Result<()> {
+        let mut manager_stats: Account<ExpenseManagerStats> = migrate_account(
+            &ctx.accounts.account.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            |_| ExpenseManagerStats::MAX_SIZE + 8,
+        )?;
+
+        manager_stats.version = ExpenseManagerStats::VERSION;
+        manager_stats.exit(ctx.program_id)?;
+
+        Ok(())
+    }
+    pub fn fund_expense_manager(ctx: Context<FundExpenseManager>, amount: u64) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let funder = &ctx.accounts.funder;
+        let system_program = &ctx.accounts.system_program;
+
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: funder.to_account_info(),
+                    to: expense_manager.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            funder.key(),
+            AuditAction::ManagerFunded,
+            funder.key(),
+            amount,
+            timestamp,
+        );
+        emit!(ExpenseManagerFunded {
+            expense_manager: expense_manager.key(),
+            funder: funder.key(),
+            amount,
+            timestamp,
+        });
 
         Ok(())
     }
@@ -57,11 +236,83 @@ pub mod slide {
     ) -> Result<()> {
         let governance_authority = &ctx.accounts.governance_authority;
         let expense_manager = &mut ctx.accounts.expense_manager;
+        let member = &ctx.accounts.member;
+
+        expense_manager.external_program_id = external_program_id;
+        expense_manager.realm = Some(realm);
+        expense_manager.governance_authority = Some(governance_authority.key());
+        expense_manager.governance_type = Some(governance_type);
+
+        emit!(ExpenseManagerInitialized {
+            expense_manager: expense_manager.key(),
+            external_program_id,
+            realm: expense_manager.realm,
+            governance_authority: expense_manager.governance_authority,
+            squad: None,
+            member: member.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_create_expense_manager(
+        ctx: Context<SPLGovCreateExpenseManager>,
+        realm: Pubkey,
+        governance_type: GovernanceType,
+        external_program_id: Pubkey,
+        name: String,
+        membership_token_mint: Pubkey,
+    ) -> Result<()> {
+        let governance_authority = &ctx.accounts.governance_authority;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let member = &ctx.accounts.member;
 
+        expense_manager.name = name;
+        require!(expense_manager.validate(), SlideError::DataTooLarge);
+        expense_manager.membership_token_mint = membership_token_mint;
+        expense_manager.namespace = Some(realm);
+        expense_manager.version = ExpenseManager::VERSION;
+        expense_manager.bump = *ctx
+            .bumps
+            .get("expense_manager")
+            .ok_or(SlideError::BumpNotFound)?;
         expense_manager.external_program_id = external_program_id;
         expense_manager.realm = Some(realm);
         expense_manager.governance_authority = Some(governance_authority.key());
         expense_manager.governance_type = Some(governance_type);
+
+        let mut audit_log = ctx.accounts.audit_log.load_init()?;
+        audit_log.expense_manager = expense_manager.key();
+        audit_log.bump = *ctx.bumps.get("audit_log").ok_or(SlideError::BumpNotFound)?;
+
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        manager_stats.expense_manager = expense_manager.key();
+        manager_stats.bump = *ctx
+            .bumps
+            .get("manager_stats")
+            .ok_or(SlideError::BumpNotFound)?;
+        manager_stats.version = ExpenseManagerStats::VERSION;
+        manager_stats.counts_all_packages = true;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(ExpenseManagerCreated {
+            expense_manager: expense_manager.key(),
+            namespace: expense_manager.namespace,
+            name: expense_manager.name.clone(),
+            membership_token_mint,
+            payer: member.key(),
+            timestamp,
+        });
+        emit!(ExpenseManagerInitialized {
+            expense_manager: expense_manager.key(),
+            external_program_id,
+            realm: expense_manager.realm,
+            governance_authority: expense_manager.governance_authority,
+            squad: None,
+            member: member.key(),
+            timestamp,
+        });
+
         Ok(())
     }
     pub fn spl_gov_create_access_record(
@@ -70,13 +321,37 @@ pub mod slide {
         user: Pubkey,
         role: Role,
     ) -> Result<()> {
-        let expense_manager = &ctx.accounts.expense_manager;
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_manager = &mut ctx.accounts.expense_manager;
         let access_record = &mut ctx.accounts.access_record;
 
-        access_record.bump = *ctx.bumps.get("access_record").unwrap();
+        access_record.bump = *ctx
+            .bumps
+            .get("access_record")
+            .ok_or(SlideError::BumpNotFound)?;
         access_record.user = user;
         access_record.expense_manager = expense_manager.key();
         access_record.role = role;
+        access_record.version = AccessRecord::VERSION;
+        expense_manager.access_record_count = expense_manager
+            .access_record_count
+            .checked_add(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            ctx.accounts.governance_authority.key(),
+            AuditAction::AccessGranted,
+            user,
+            0,
+            timestamp,
+        );
+        emit!(AccessGranted {
+            expense_manager: expense_manager.key(),
+            user,
+            role: access_record.role.clone(),
+            timestamp,
+        });
 
         Ok(())
     }
@@ -85,57 +360,254 @@ pub mod slide {
         _realm: Pubkey,
         withdrawal_amount: u64,
     ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
         let expense_manager = &mut ctx.accounts.expense_manager;
         let native_treasury = &mut ctx.accounts.native_treasury;
+        let manager_stats = &mut ctx.accounts.manager_stats;
 
         let manager_info = expense_manager.to_account_info();
         let native_treasury_info = native_treasury.to_account_info();
 
-        let mut manager_balance = manager_info.try_borrow_mut_lamports()?;
-        let mut native_treasury_balance = native_treasury_info.try_borrow_mut_lamports()?;
+        pay_from_manager(&manager_info, &native_treasury_info, withdrawal_amount)?;
+        manager_stats.record_withdrawal(withdrawal_amount)?;
 
-        // need to determine manager balance aside from rent-exemption
-        let rent = Rent::get()?;
-        let rent_exempt_lamports = rent.minimum_balance(manager_info.data_len()).max(1);
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            ctx.accounts.governance_authority.key(),
+            AuditAction::ManagerWithdrawal,
+            native_treasury_info.key(),
+            withdrawal_amount,
+            timestamp,
+        );
+        emit!(ExpenseManagerWithdrawal {
+            expense_manager: manager_info.key(),
+            treasury: native_treasury_info.key(),
+            amount: withdrawal_amount,
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_queue_withdrawal(
+        ctx: Context<SPLGovQueueWithdrawal>,
+        _realm: Pubkey,
+        _nonce: u32,
+        withdrawal_amount: u64,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let native_treasury = &ctx.accounts.native_treasury;
+        let withdrawal_request = &mut ctx.accounts.withdrawal_request;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        withdrawal_request.bump = *ctx
+            .bumps
+            .get("withdrawal_request")
+            .ok_or(SlideError::BumpNotFound)?;
+        withdrawal_request.expense_manager = expense_manager.key();
+        withdrawal_request.destination = native_treasury.key();
+        withdrawal_request.amount = withdrawal_amount;
+        withdrawal_request.requested_at = timestamp;
+        withdrawal_request.executable_at =
+            timestamp.saturating_add(expense_manager.withdrawal_timelock.unwrap_or(0) as i64);
+        withdrawal_request.payer = native_treasury.key();
+        withdrawal_request.version = WithdrawalRequest::VERSION;
+        expense_manager.withdrawal_request_count = expense_manager
+            .withdrawal_request_count
+            .checked_add(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
 
-        let max_withdrawal = manager_balance.checked_sub(rent_exempt_lamports).unwrap();
+        emit!(WithdrawalRequested {
+            expense_manager: expense_manager.key(),
+            withdrawal_request: withdrawal_request.key(),
+            destination: native_treasury.key(),
+            amount: withdrawal_amount,
+            executable_at: withdrawal_request.executable_at,
+            timestamp,
+        });
 
-        require!(
-            withdrawal_amount <= max_withdrawal,
-            SlideError::ManagerInsufficientFunds
+        Ok(())
+    }
+    pub fn spl_gov_close_expense_manager(
+        ctx: Context<SPLGovCloseExpenseManager>,
+        _realm: Pubkey,
+        force_deny_nonces: Vec<u32>,
+    ) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let governance_authority = &ctx.accounts.governance_authority;
+        let native_treasury = &ctx.accounts.native_treasury;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        settle_expense_manager(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &ctx.accounts.manager_metadata.to_account_info(),
+            &governance_authority.key(),
+            native_treasury,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            &force_deny_nonces,
+            ctx.program_id,
+        )?;
+
+        // the remaining lamports are swept to the treasury when the manager is closed
+        let amount = expense_manager.to_account_info().lamports();
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.append(
+            governance_authority.key(),
+            AuditAction::ManagerClosed,
+            native_treasury.key(),
+            amount,
+            timestamp,
         );
+        emit!(ExpenseManagerClosed {
+            expense_manager: expense_manager.key(),
+            treasury: native_treasury.key(),
+            amount,
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_unpause_expense_manager(
+        ctx: Context<SPLGovUpdateExpenseManager>,
+        _realm: Pubkey,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let governance_authority = &ctx.accounts.governance_authority;
+
+        expense_manager.paused = false;
+
+        emit!(ExpenseManagerUnpaused {
+            expense_manager: expense_manager.key(),
+            authority: governance_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_set_withdrawal_timelock(
+        ctx: Context<SPLGovUpdateExpenseManager>,
+        _realm: Pubkey,
+        withdrawal_timelock: Option<u32>,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        expense_manager
+            .set_withdrawal_timelock(withdrawal_timelock, Clock::get()?.unix_timestamp)?;
+
+        Ok(())
+    }
+    pub fn spl_gov_approve_recurring_expense(
+        ctx: Context<SPLGovApproveRecurringExpense>,
+        _realm: Pubkey,
+    ) -> Result<()> {
+        let recurring_expense = &mut ctx.accounts.recurring_expense;
+        let governance_authority = &ctx.accounts.governance_authority;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        recurring_expense.approve(governance_authority.key(), timestamp);
+
+        emit!(RecurringExpenseApproved {
+            expense_manager: recurring_expense.expense_manager,
+            recurring_expense: recurring_expense.key(),
+            approved_by: governance_authority.key(),
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_update_manager_metadata(
+        ctx: Context<SPLGovUpdateManagerMetadata>,
+        _realm: Pubkey,
+        display_name: String,
+        description: String,
+        logo_uri: String,
+        contact: String,
+    ) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let manager_metadata = &mut ctx.accounts.manager_metadata;
+
+        manager_metadata.display_name = display_name;
+        manager_metadata.description = description;
+        manager_metadata.logo_uri = logo_uri;
+        manager_metadata.contact = contact;
+        require!(manager_metadata.validate(), SlideError::DataTooLarge);
+
+        emit!(ExpenseManagerMetadataUpdated {
+            expense_manager: expense_manager.key(),
+            display_name: manager_metadata.display_name.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn spl_gov_set_membership_token_mint(
+        ctx: Context<SPLGovSetMembershipTokenMint>,
+        _realm: Pubkey,
+        membership_token_mint: Pubkey,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        let previous_mint = expense_manager.membership_token_mint;
+        expense_manager.membership_token_mint = membership_token_mint;
 
-        **manager_balance = manager_balance.checked_sub(withdrawal_amount).unwrap();
-        **native_treasury_balance = native_treasury_balance
-            .checked_add(withdrawal_amount)
-            .unwrap();
+        emit!(MembershipTokenMintUpdated {
+            expense_manager: expense_manager.key(),
+            previous_mint,
+            membership_token_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
     pub fn spl_gov_create_expense_package(
         ctx: Context<SPLGovCreateExpensePackage>,
         _realm: Pubkey,
-        _nonce: u32, // assuming constraint has already verified nonce value,
+        nonce: u32, // assuming constraint has already verified nonce value,
         name: String,
         description: String,
         quantity: u64,
+        category: Option<u8>,
     ) -> Result<()> {
         let owner = &ctx.accounts.owner;
-        let expense_manager = &mut ctx.accounts.expense_manager;
+        let expense_manager = &ctx.accounts.expense_manager;
         let expense_package = &mut ctx.accounts.expense_package;
+        let user_data = &mut ctx.accounts.user_data;
+        let manager_stats = &mut ctx.accounts.manager_stats;
 
-        expense_package.bump = *ctx.bumps.get("expense_package").unwrap();
+        expense_package.bump = *ctx
+            .bumps
+            .get("expense_package")
+            .ok_or(SlideError::BumpNotFound)?;
         expense_package.expense_manager = expense_manager.key();
         expense_package.owner = owner.key();
         expense_package.name = name;
         expense_package.description = description;
+        require!(expense_package.validate(), SlideError::DataTooLarge);
         expense_package.quantity = quantity;
-        expense_package.nonce = expense_manager.expense_package_nonce;
-
-        expense_manager.expense_package_nonce = expense_manager
-            .expense_package_nonce
-            .checked_add(1)
-            .unwrap();
+        expense_package.nonce = nonce;
+        expense_package.category = category;
+        expense_package.version = ExpensePackage::VERSION;
+        manager_stats.move_package(None, &ExpensePackageState::Created)?;
+
+        if user_data.user == Pubkey::default() {
+            user_data.bump = *ctx.bumps.get("user_data").ok_or(SlideError::BumpNotFound)?;
+            user_data.user = owner.key();
+            user_data.version = UserData::VERSION;
+        }
+        user_data.expense_package_nonce =
+            nonce.checked_add(1).ok_or(SlideError::ArithmeticOverflow)?;
+
+        emit!(ExpensePackageCreated {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            owner: owner.key(),
+            nonce,
+            quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
@@ -151,7 +623,23 @@ pub mod slide {
 
         expense_package.name = name;
         expense_package.description = description;
-        expense_package.quantity = quantity;
+        require!(expense_package.validate(), SlideError::DataTooLarge);
+        if expense_package.line_items.is_empty() {
+            expense_package.quantity = quantity;
+        } else {
+            require!(
+                quantity == expense_package.quantity,
+                SlideError::QuantityDerivedFromLineItems
+            );
+        }
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
@@ -159,11 +647,39 @@ pub mod slide {
         ctx: Context<SPLGovSubmitExpensePackage>,
         _realm: Pubkey,
         _nonce: u32,
+        payee: Option<Pubkey>,
     ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
         let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let manager_stats = &mut ctx.accounts.manager_stats;
 
         // TODO: auto-approve logic
+        expense_package.payee = Some(payee.unwrap_or_else(|| owner.key()));
         expense_package.state = ExpensePackageState::Pending;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Created),
+            &ExpensePackageState::Pending,
+        )?;
+        manager_stats.record_submission(expense_package.quantity)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        expense_package.submitted_at = timestamp;
+        audit_log.load_mut()?.append(
+            owner.key(),
+            AuditAction::PackageSubmitted,
+            expense_package.key(),
+            expense_package.quantity,
+            timestamp,
+        );
+        emit!(ExpensePackageSubmitted {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: owner.key(),
+            payee: expense_package.payout_address(),
+            quantity: expense_package.quantity,
+            timestamp,
+        });
 
         Ok(())
     }
@@ -171,174 +687,1174 @@ pub mod slide {
         ctx: Context<SPLGovApproveExpensePackage>,
         _realm: Pubkey,
         _nonce: u32,
+        approved_amount: Option<u64>,
+        vesting: Option<VestingSchedule>,
     ) -> Result<()> {
         let expense_package = &mut ctx.accounts.expense_package;
         let expense_manager = &mut ctx.accounts.expense_manager;
-
-        expense_package.state = ExpensePackageState::Approved;
-
-        let package_info = expense_package.to_account_info();
-        let mut package_balance = package_info.try_borrow_mut_lamports()?;
-        let manager_info = expense_manager.to_account_info();
-        let mut manager_balance = manager_info.try_borrow_mut_lamports()?;
-
-        **manager_balance = manager_balance
-            .checked_sub(expense_package.quantity)
-            .unwrap();
-        **package_balance = package_balance
-            .checked_add(expense_package.quantity)
-            .unwrap();
-
-        Ok(())
+        let authority = &ctx.accounts.authority;
+        let payee = &ctx.accounts.payee;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        approve_expense_package(
+            expense_package,
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            payee,
+            approved_amount,
+            vesting,
+        )
     }
     pub fn spl_gov_deny_expense_package(
         ctx: Context<SPLGovDenyExpensePackage>,
         _realm: Pubkey,
         _nonce: u32,
     ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
         let expense_package = &mut ctx.accounts.expense_package;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_manager.check_category_reviewer(expense_package.category, &authority.key())?;
 
         expense_package.state = ExpensePackageState::Denied;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Pending),
+            &ExpensePackageState::Denied,
+        )?;
+        manager_stats.record_denial(expense_package.quantity)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            authority.key(),
+            AuditAction::PackageDenied,
+            expense_package.key(),
+            0,
+            timestamp,
+        );
+        emit!(ExpensePackageDenied {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            reviewer: authority.key(),
+            timestamp,
+        });
 
         Ok(())
     }
-    pub fn withdraw_from_expense_package(
-        ctx: Context<WithdrawFromExpensePackage>,
-        _nonce: u32,
+    pub fn spl_gov_approve_expense_packages(
+        ctx: Context<SPLGovReviewExpensePackages>,
+        _realm: Pubkey,
+        nonces: Vec<u32>,
+        approved_amounts: Vec<Option<u64>>,
     ) -> Result<()> {
-        let expense_package = &mut ctx.accounts.expense_package;
-        let owner = &mut ctx.accounts.owner;
-
-        let expense_package_info = expense_package.to_account_info();
-        let mut expense_package_balance = expense_package_info.try_borrow_mut_lamports()?;
-        let owner_info = owner.to_account_info();
-        let mut owner_balance = owner_info.try_borrow_mut_lamports()?;
-        let reimbursement_amount = expense_package.quantity;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        approve_expense_package_batch(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            ctx.remaining_accounts,
+            &nonces,
+            &approved_amounts,
+            ctx.program_id,
+        )
+    }
+    pub fn spl_gov_deny_expense_packages(
+        ctx: Context<SPLGovReviewExpensePackages>,
+        _realm: Pubkey,
+        nonces: Vec<u32>,
+    ) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        deny_expense_package_batch(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            ctx.remaining_accounts,
+            &nonces,
+            ctx.program_id,
+        )
+    }
+    pub fn execute_withdrawal_request(ctx: Context<ExecuteWithdrawalRequest>) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let withdrawal_request = &ctx.accounts.withdrawal_request;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let destination = &ctx.accounts.destination;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        let executor = &ctx.accounts.executor;
 
-        **expense_package_balance = expense_package_balance
-            .checked_sub(reimbursement_amount)
-            .unwrap();
-        **owner_balance = owner_balance.checked_add(reimbursement_amount).unwrap();
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            timestamp >= withdrawal_request.executable_at,
+            SlideError::WithdrawalRequestLocked
+        );
 
-        expense_package.state = ExpensePackageState::Paid;
+        let manager_info = expense_manager.to_account_info();
+        pay_from_manager(&manager_info, destination, withdrawal_request.amount)?;
+        manager_stats.record_withdrawal(withdrawal_request.amount)?;
+        expense_manager.withdrawal_request_count = expense_manager
+            .withdrawal_request_count
+            .checked_sub(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        audit_log.load_mut()?.append(
+            executor.key(),
+            AuditAction::ManagerWithdrawal,
+            destination.key(),
+            withdrawal_request.amount,
+            timestamp,
+        );
+        emit!(ExpenseManagerWithdrawal {
+            expense_manager: expense_manager.key(),
+            treasury: destination.key(),
+            amount: withdrawal_request.amount,
+            timestamp,
+        });
 
         Ok(())
     }
-    pub fn squads_initialize_expense_manager(
-        ctx: Context<SquadsInitializeExpenseManager>,
-        external_program_id: Pubkey,
-    ) -> Result<()> {
+    pub fn cancel_withdrawal_request(ctx: Context<CancelWithdrawalRequest>) -> Result<()> {
+        let withdrawal_request = &ctx.accounts.withdrawal_request;
         let expense_manager = &mut ctx.accounts.expense_manager;
-        let squad = &ctx.accounts.squad;
+        let authority = &ctx.accounts.authority;
 
-        expense_manager.external_program_id = external_program_id;
-        expense_manager.squad = Some(squad.key());
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            timestamp < withdrawal_request.executable_at,
+            SlideError::WithdrawalRequestExecutable
+        );
+        expense_manager.withdrawal_request_count = expense_manager
+            .withdrawal_request_count
+            .checked_sub(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        emit!(WithdrawalRequestCancelled {
+            expense_manager: expense_manager.key(),
+            withdrawal_request: withdrawal_request.key(),
+            authority: authority.key(),
+            timestamp,
+        });
 
         Ok(())
     }
-    pub fn squads_create_expense_package(
-        ctx: Context<SquadsCreateExpensePackage>,
-        _nonce: u32,
-        name: String,
-        description: String,
-        quantity: u64,
-    ) -> Result<()> {
-        let owner = &ctx.accounts.owner;
+    pub fn crank_expense_package(ctx: Context<CrankExpensePackage>, _nonce: u32) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
         let expense_package = &mut ctx.accounts.expense_package;
         let expense_manager = &mut ctx.accounts.expense_manager;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        let cranker = &ctx.accounts.cranker;
 
-        expense_package.bump = *ctx.bumps.get("expense_package").unwrap();
-        expense_package.expense_manager = expense_manager.key();
-        expense_package.owner = owner.key();
-        expense_package.name = name;
-        expense_package.description = description;
-        expense_package.quantity = quantity;
-        expense_package.nonce = expense_manager.expense_package_nonce;
+        let timestamp = Clock::get()?.unix_timestamp;
+        let is_past = |since: i64, limit: Option<u32>| {
+            // packages from before these timestamps were tracked are never lapsed
+            since > 0 && limit.map_or(false, |limit| timestamp - since > limit as i64)
+        };
 
-        expense_manager.expense_package_nonce = expense_manager
-            .expense_package_nonce
-            .checked_add(1)
-            .unwrap();
+        let previous_state = expense_package.state.clone();
+        let swept_amount = match previous_state {
+            ExpensePackageState::Pending
+                if is_past(
+                    expense_package.submitted_at,
+                    expense_manager.max_pending_age,
+                ) =>
+            {
+                0
+            }
+            ExpensePackageState::Pending
+                if !expense_package.escalated
+                    && is_past(expense_package.submitted_at, expense_manager.review_sla) =>
+            {
+                expense_package.escalated = true;
+                emit!(ExpensePackageEscalated {
+                    expense_manager: expense_manager.key(),
+                    expense_package: expense_package.key(),
+                    timestamp,
+                });
+                return Ok(());
+            }
+            // vesting packages only start their deadline once fully vested
+            ExpensePackageState::Approved
+                if is_past(
+                    expense_package.fully_vested_at(),
+                    expense_package.withdrawal_deadline,
+                ) =>
+            {
+                let remaining_amount = expense_package.remaining_amount();
+                transfer_lamports(
+                    &expense_package.to_account_info(),
+                    &expense_manager.to_account_info(),
+                    remaining_amount,
+                )?;
+                // the swept funds were never spent, so they no longer count as approved
+                expense_manager.release_category_spend(expense_package.category, remaining_amount);
+                manager_stats.reverse_approval(remaining_amount);
+                remaining_amount
+            }
+            _ => return err!(SlideError::PackageNotOverdue),
+        };
+
+        expense_package.state = ExpensePackageState::Expired;
+        manager_stats.move_package(Some(&previous_state), &ExpensePackageState::Expired)?;
+        if previous_state == ExpensePackageState::Approved && expense_package.advance {
+            manager_stats.close_advance();
+        }
+
+        audit_log.load_mut()?.append(
+            cranker.key(),
+            AuditAction::PackageExpired,
+            expense_package.key(),
+            swept_amount,
+            timestamp,
+        );
+        emit!(ExpensePackageExpired {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            amount: swept_amount,
+            timestamp,
+        });
 
         Ok(())
     }
-    pub fn squads_update_expense_package(
-        ctx: Context<SquadsUpdateExpensePackage>,
-        _nonce: u32,
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recurring_expense(
+        ctx: Context<CreateRecurringExpense>,
+        nonce: u32,
         name: String,
-        description: String,
-        quantity: u64,
+        amount: u64,
+        period: u32,
+        start: i64,
+        end: Option<i64>,
+        cap: Option<u64>,
+        category: Option<u8>,
+        payee: Option<Pubkey>,
     ) -> Result<()> {
-        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let recurring_expense = &mut ctx.accounts.recurring_expense;
 
-        expense_package.name = name;
-        expense_package.description = description;
-        expense_package.quantity = quantity;
+        require!(
+            amount > 0 && period > 0,
+            SlideError::InvalidRecurringSchedule
+        );
+        // without an end or a cap, payouts would go on until the manager runs dry
+        require!(
+            end.is_some() || cap.is_some(),
+            SlideError::RecurringExpenseUnbounded
+        );
+
+        recurring_expense.bump = *ctx
+            .bumps
+            .get("recurring_expense")
+            .ok_or(SlideError::BumpNotFound)?;
+        recurring_expense.owner = owner.key();
+        recurring_expense.expense_manager = expense_manager.key();
+        recurring_expense.payee = payee.unwrap_or_else(|| owner.key());
+        recurring_expense.name = name;
+        require!(recurring_expense.validate(), SlideError::DataTooLarge);
+        recurring_expense.amount = amount;
+        recurring_expense.period = period;
+        recurring_expense.next_payout_at = start;
+        recurring_expense.end = end;
+        recurring_expense.cap = cap;
+        recurring_expense.category = category;
+        recurring_expense.nonce = nonce;
+        recurring_expense.version = RecurringExpense::VERSION;
+
+        emit!(RecurringExpenseCreated {
+            expense_manager: expense_manager.key(),
+            recurring_expense: recurring_expense.key(),
+            owner: owner.key(),
+            amount,
+            period,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
-    pub fn squads_submit_expense_package(
-        ctx: Context<SquadsSubmitExpensePackage>,
-        _nonce: u32,
-    ) -> Result<()> {
-        let expense_package = &mut ctx.accounts.expense_package;
+    pub fn approve_recurring_expense(ctx: Context<ApproveRecurringExpense>) -> Result<()> {
+        let recurring_expense = &mut ctx.accounts.recurring_expense;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
 
-        expense_package.state = ExpensePackageState::Pending;
+        expense_manager.check_category_reviewer(recurring_expense.category, &authority.key())?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        recurring_expense.approve(authority.key(), timestamp);
+
+        emit!(RecurringExpenseApproved {
+            expense_manager: expense_manager.key(),
+            recurring_expense: recurring_expense.key(),
+            approved_by: authority.key(),
+            timestamp,
+        });
 
         Ok(())
     }
-    pub fn squads_execute_access_proposal(ctx: Context<SquadsExecuteAccessProposal>) -> Result<()> {
-        let proposal = &ctx.accounts.proposal;
-        let proposal_execution = &mut ctx.accounts.proposal_execution;
+    pub fn pay_recurring_expense(ctx: Context<PayRecurringExpense>) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let recurring_expense = &mut ctx.accounts.recurring_expense;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let payee = &ctx.accounts.payee;
+        let manager_stats = &mut ctx.accounts.manager_stats;
         let squad = &ctx.accounts.squad;
-        let squad_mint = &ctx.accounts.squad_mint;
-        let member = &ctx.accounts.member;
-        let expense_manager = &ctx.accounts.expense_manager;
-        let access_record = &mut ctx.accounts.access_record;
+        let cranker = &ctx.accounts.cranker;
 
-        // TODO: move vote logic, validation logic elsewhere
+        check_squad_unlocked(expense_manager, squad)?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            timestamp >= recurring_expense.next_payout_at,
+            SlideError::RecurringExpenseNotDue
+        );
+        require!(
+            !recurring_expense.is_complete(),
+            SlideError::RecurringExpenseComplete
+        );
 
-        if proposal.votes_num != 2 {
-            return err!(SlideError::InvalidProposal);
-        }
+        let amount = recurring_expense.amount;
+        expense_manager.record_category_spend(recurring_expense.category, amount)?;
+        pay_from_manager(&expense_manager.to_account_info(), payee, amount)?;
+        manager_stats.record_approval(amount)?;
+        manager_stats.record_payment(amount)?;
+
+        // late cranks catch up one period at a time rather than skipping payouts
+        recurring_expense.next_payout_at = recurring_expense
+            .next_payout_at
+            .checked_add(recurring_expense.period as i64)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+        recurring_expense.total_paid = recurring_expense
+            .total_paid
+            .checked_add(amount)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        audit_log.load_mut()?.append(
+            cranker.key(),
+            AuditAction::RecurringExpensePaid,
+            recurring_expense.key(),
+            amount,
+            timestamp,
+        );
+        emit!(RecurringExpensePaid {
+            expense_manager: expense_manager.key(),
+            recurring_expense: recurring_expense.key(),
+            payee: payee.key(),
+            amount,
+            timestamp,
+        });
 
-        if proposal.votes_labels.get(0).unwrap().trim_end() != "Approve"
-            || proposal.votes_labels.get(1).unwrap().trim_end() != "Deny"
-        {
-            return err!(SlideError::InvalidProposal);
+        Ok(())
+    }
+    pub fn close_recurring_expense(_ctx: Context<CloseRecurringExpense>) -> Result<()> {
+        Ok(())
+    }
+    pub fn withdraw_from_expense_package(
+        ctx: Context<WithdrawFromExpensePackage>,
+        _nonce: u32,
+    ) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let payee = &ctx.accounts.payee;
+        let squad = &ctx.accounts.squad;
+        let owner = &ctx.accounts.owner;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        check_squad_unlocked(expense_manager, squad)?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        let reimbursement_amount = expense_package.release(timestamp)?;
+        transfer_lamports(
+            &expense_package.to_account_info(),
+            &payee.to_account_info(),
+            reimbursement_amount,
+        )?;
+
+        if expense_package.state == ExpensePackageState::Paid {
+            manager_stats.move_package(
+                Some(&ExpensePackageState::Approved),
+                &ExpensePackageState::Paid,
+            )?;
         }
+        manager_stats.record_payment(reimbursement_amount)?;
+
+        audit_log.load_mut()?.append(
+            owner.key(),
+            AuditAction::PackagePaid,
+            expense_package.key(),
+            reimbursement_amount,
+            timestamp,
+        );
+        emit!(ExpensePackagePaid {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            payee: payee.key(),
+            authority: owner.key(),
+            amount: reimbursement_amount,
+            timestamp,
+        });
 
-        let pass_votes = *proposal.votes.get(0).unwrap();
-        let fail_votes = *proposal.votes.get(1).unwrap();
-        if pass_votes < fail_votes {
-            return err!(SlideError::InvalidProposal);
+        Ok(())
+    }
+    pub fn pay_expense_package(ctx: Context<PayExpensePackage>, _nonce: u32) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let payee = &ctx.accounts.payee;
+        let squad = &ctx.accounts.squad;
+        let authority = &ctx.accounts.authority;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        check_squad_unlocked(expense_manager, squad)?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        let reimbursement_amount = expense_package.release(timestamp)?;
+        transfer_lamports(
+            &expense_package.to_account_info(),
+            &payee.to_account_info(),
+            reimbursement_amount,
+        )?;
+
+        if expense_package.state == ExpensePackageState::Paid {
+            manager_stats.move_package(
+                Some(&ExpensePackageState::Approved),
+                &ExpensePackageState::Paid,
+            )?;
         }
+        manager_stats.record_payment(reimbursement_amount)?;
+
+        audit_log.load_mut()?.append(
+            authority.key(),
+            AuditAction::PackagePaid,
+            expense_package.key(),
+            reimbursement_amount,
+            timestamp,
+        );
+        emit!(ExpensePackagePaid {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            payee: payee.key(),
+            authority: authority.key(),
+            amount: reimbursement_amount,
+            timestamp,
+        });
 
-        // check quorum & support
-        let curr_quorum_percent;
-        let current_support_percent;
-        if proposal.execute_ready {
-            curr_quorum_percent =
-                (proposal.has_voted.len() as f32 / proposal.members_at_execute as f32) * 100.0;
+        Ok(())
+    }
+    pub fn pay_expense_packages(ctx: Context<PayExpensePackages>, nonces: Vec<u32>) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let squad = &ctx.accounts.squad;
+        let authority = &ctx.accounts.authority;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        check_squad_unlocked(expense_manager, squad)?;
+        pay_expense_package_batch(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            ctx.remaining_accounts,
+            &nonces,
+            ctx.program_id,
+        )
+    }
+    pub fn add_expense_line_item(
+        ctx: Context<EditExpenseLineItems>,
+        _nonce: u32,
+        description: String,
+        amount: u64,
+        category: String,
+        date: i64,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
 
-            current_support_percent =
-                (pass_votes as f32 / proposal.supply_at_execute as f32) * 100.0;
-        } else {
-            curr_quorum_percent =
-                (proposal.has_voted.len() as f32 / squad.members.len() as f32) * 100.0;
+        let line_item = ExpenseLineItem {
+            description,
+            amount,
+            category,
+            date,
+        };
+        require!(line_item.validate(), SlideError::DataTooLarge);
+        require!(
+            expense_package.line_items.len() < ExpensePackage::MAX_LINE_ITEMS,
+            SlideError::TooManyLineItems
+        );
 
-            current_support_percent = (pass_votes as f32 / squad_mint.supply as f32) * 100.0;
+        expense_package.line_items.push(line_item);
+        expense_package.quantity = expense_package
+            .line_items_total()
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        resize_account(
+            &expense_package.to_account_info(),
+            expense_package.space(),
+            &owner.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn update_expense_line_item(
+        ctx: Context<EditExpenseLineItems>,
+        _nonce: u32,
+        index: u8,
+        description: String,
+        amount: u64,
+        category: String,
+        date: i64,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+
+        let line_item = ExpenseLineItem {
+            description,
+            amount,
+            category,
+            date,
+        };
+        require!(line_item.validate(), SlideError::DataTooLarge);
+
+        *expense_package
+            .line_items
+            .get_mut(index as usize)
+            .ok_or(SlideError::LineItemNotFound)? = line_item;
+        expense_package.quantity = expense_package
+            .line_items_total()
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn remove_expense_line_item(
+        ctx: Context<EditExpenseLineItems>,
+        _nonce: u32,
+        index: u8,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
+
+        require!(
+            (index as usize) < expense_package.line_items.len(),
+            SlideError::LineItemNotFound
+        );
+
+        expense_package.line_items.remove(index as usize);
+        expense_package.quantity = expense_package
+            .line_items_total()
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        resize_account(
+            &expense_package.to_account_info(),
+            expense_package.space(),
+            &owner.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn set_expense_advance(
+        ctx: Context<SetExpenseAdvance>,
+        _nonce: u32,
+        advance: bool,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+
+        expense_package.advance = advance;
+
+        Ok(())
+    }
+    pub fn reconcile_expense_advance(
+        ctx: Context<ReconcileExpenseAdvance>,
+        _nonce: u32,
+        actual_amount: u64,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        let system_program = &ctx.accounts.system_program;
+
+        let advanced_amount = expense_package.approved_quantity();
+        if actual_amount < advanced_amount {
+            transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    Transfer {
+                        from: owner.to_account_info(),
+                        to: expense_package.to_account_info(),
+                    },
+                ),
+                advanced_amount - actual_amount,
+            )?;
         }
 
-        if curr_quorum_percent < squad.vote_quorum as f32 {
-            return err!(SlideError::InvalidProposal);
+        expense_package.reconciled_amount = Some(actual_amount);
+        expense_package.state = ExpensePackageState::Reconciling;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Paid),
+            &ExpensePackageState::Reconciling,
+        )?;
+
+        emit!(ExpenseAdvanceReconciliationSubmitted {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: owner.key(),
+            actual_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn approve_expense_advance(ctx: Context<ReviewExpenseAdvance>, _nonce: u32) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let payee = &ctx.accounts.payee;
+        let authority = &ctx.accounts.authority;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_manager.check_category_reviewer(expense_package.category, &authority.key())?;
+        let actual_amount = expense_package
+            .reconciled_amount
+            .ok_or(SlideError::PackageNotReconciling)?;
+        let advanced_amount = expense_package.approved_quantity();
+        let manager_info = expense_manager.to_account_info();
+        if actual_amount > advanced_amount {
+            let top_up = actual_amount - advanced_amount;
+            expense_manager.record_category_spend(expense_package.category, top_up)?;
+            pay_from_manager(&manager_info, payee, top_up)?;
+            manager_stats.record_approval(top_up)?;
+            manager_stats.record_payment(top_up)?;
+        } else if actual_amount < advanced_amount {
+            let refund = advanced_amount - actual_amount;
+            // the owner escrowed the refund in the package when reconciling
+            transfer_lamports(&expense_package.to_account_info(), &manager_info, refund)?;
+            expense_manager.release_category_spend(expense_package.category, refund);
+            manager_stats.reverse_approval(refund);
+            manager_stats.reverse_payment(refund);
         }
 
-        if current_support_percent < squad.vote_support as f32 {
-            return err!(SlideError::InvalidProposal);
+        expense_package.state = ExpensePackageState::Reconciled;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Reconciling),
+            &ExpensePackageState::Reconciled,
+        )?;
+        manager_stats.close_advance();
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            authority.key(),
+            AuditAction::AdvanceReconciled,
+            expense_package.key(),
+            actual_amount,
+            timestamp,
+        );
+        emit!(ExpenseAdvanceReconciled {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            reviewer: authority.key(),
+            actual_amount,
+            accepted: true,
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn deny_expense_advance(ctx: Context<ReviewExpenseAdvance>, _nonce: u32) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let owner = &ctx.accounts.owner;
+        let authority = &ctx.accounts.authority;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_manager.check_category_reviewer(expense_package.category, &authority.key())?;
+        let actual_amount = expense_package
+            .reconciled_amount
+            .ok_or(SlideError::PackageNotReconciling)?;
+        let advanced_amount = expense_package.approved_quantity();
+        if actual_amount < advanced_amount {
+            transfer_lamports(
+                &expense_package.to_account_info(),
+                owner,
+                advanced_amount - actual_amount,
+            )?;
         }
 
-        // title should start with [SLIDE PROPOSAL]
+        // the owner can reconcile again with corrected receipts
+        expense_package.reconciled_amount = None;
+        expense_package.state = ExpensePackageState::Paid;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Reconciling),
+            &ExpensePackageState::Paid,
+        )?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            authority.key(),
+            AuditAction::AdvanceRejected,
+            expense_package.key(),
+            actual_amount,
+            timestamp,
+        );
+        emit!(ExpenseAdvanceReconciled {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            reviewer: authority.key(),
+            actual_amount,
+            accepted: false,
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn add_expense_attachment(
+        ctx: Context<EditExpenseAttachments>,
+        _nonce: u32,
+        scheme: UriScheme,
+        content_hash: [u8; 32],
+        uri: String,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
+
+        let attachment = ExpenseAttachment {
+            scheme,
+            content_hash,
+            uri,
+        };
+        require!(attachment.validate(), SlideError::DataTooLarge);
+        require!(
+            expense_package.attachments.len() < ExpensePackage::MAX_ATTACHMENTS,
+            SlideError::TooManyAttachments
+        );
+
+        expense_package.attachments.push(attachment);
+
+        resize_account(
+            &expense_package.to_account_info(),
+            expense_package.space(),
+            &owner.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn remove_expense_attachment(
+        ctx: Context<EditExpenseAttachments>,
+        _nonce: u32,
+        index: u8,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
+
+        require!(
+            (index as usize) < expense_package.attachments.len(),
+            SlideError::AttachmentNotFound
+        );
+
+        expense_package.attachments.remove(index as usize);
+
+        resize_account(
+            &expense_package.to_account_info(),
+            expense_package.space(),
+            &owner.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn set_attachment_policy(
+        ctx: Context<UpdateExpenseManagerConfig>,
+        require_attachments: bool,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        expense_manager.require_attachments = require_attachments;
+
+        Ok(())
+    }
+    pub fn add_expense_category(
+        ctx: Context<EditExpenseCategories>,
+        name: String,
+        budget: Option<u64>,
+        reviewers: Vec<Pubkey>,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let system_program = &ctx.accounts.system_program;
+
+        let category = ExpenseCategory {
+            name,
+            budget,
+            spent: 0,
+            reviewers,
+        };
+        require!(category.validate(), SlideError::DataTooLarge);
+        require!(
+            expense_manager.categories.len() < ExpenseManager::MAX_CATEGORIES,
+            SlideError::TooManyCategories
+        );
+
+        expense_manager.categories.push(category);
+
+        resize_account(
+            &expense_manager.to_account_info(),
+            expense_manager.space(),
+            &authority.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+
+        Ok(())
+    }
+    pub fn update_expense_category(
+        ctx: Context<EditExpenseCategories>,
+        index: u8,
+        name: String,
+        budget: Option<u64>,
+        reviewers: Vec<Pubkey>,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        let category = expense_manager
+            .categories
+            .get_mut(index as usize)
+            .ok_or(SlideError::CategoryNotFound)?;
+        let updated_category = ExpenseCategory {
+            name,
+            budget,
+            spent: category.spent,
+            reviewers,
+        };
+        require!(updated_category.validate(), SlideError::DataTooLarge);
+
+        *category = updated_category;
+
+        Ok(())
+    }
+    pub fn set_direct_payout(
+        ctx: Context<UpdateExpenseManagerConfig>,
+        direct_payout: bool,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        expense_manager.direct_payout = direct_payout;
+
+        Ok(())
+    }
+    pub fn pause_expense_manager(ctx: Context<UpdateExpenseManagerConfig>) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+
+        expense_manager.paused = true;
+
+        emit!(ExpenseManagerPaused {
+            expense_manager: expense_manager.key(),
+            authority: authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn apply_withdrawal_timelock(ctx: Context<ApplyWithdrawalTimelock>) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        let pending = expense_manager
+            .pending_withdrawal_timelock
+            .ok_or(SlideError::NoPendingTimelockChange)?;
+        require!(
+            Clock::get()?.unix_timestamp >= expense_manager.pending_timelock_effective_at,
+            SlideError::TimelockChangeNotReady
+        );
+        expense_manager.withdrawal_timelock = if pending == 0 { None } else { Some(pending) };
+        expense_manager.pending_withdrawal_timelock = None;
+
+        Ok(())
+    }
+    pub fn set_package_deadlines(
+        ctx: Context<UpdateExpenseManagerConfig>,
+        review_sla: Option<u32>,
+        max_pending_age: Option<u32>,
+        withdrawal_deadline: Option<u32>,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        expense_manager.review_sla = review_sla;
+        expense_manager.max_pending_age = max_pending_age;
+        expense_manager.withdrawal_deadline = withdrawal_deadline;
+
+        Ok(())
+    }
+    pub fn squads_initialize_expense_manager(
+        ctx: Context<SquadsInitializeExpenseManager>,
+        external_program_id: Pubkey,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let squad = &ctx.accounts.squad;
+        let proposal_watermark = &ctx.accounts.proposal_watermark;
+        let member = &ctx.accounts.member;
+
+        expense_manager.external_program_id = external_program_id;
+        expense_manager.squad = Some(squad.key());
+        let previous_watermark = previous_proposal_watermark(proposal_watermark, ctx.program_id)?;
+        expense_manager.min_proposal_index =
+            expense_manager.min_proposal_index.max(previous_watermark);
+
+        emit!(ExpenseManagerInitialized {
+            expense_manager: expense_manager.key(),
+            external_program_id,
+            realm: None,
+            governance_authority: None,
+            squad: expense_manager.squad,
+            member: member.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_create_expense_manager(
+        ctx: Context<SquadsCreateExpenseManager>,
+        external_program_id: Pubkey,
+        name: String,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let squad = &ctx.accounts.squad;
+        let proposal_watermark = &ctx.accounts.proposal_watermark;
+        let member = &ctx.accounts.member;
+
+        expense_manager.name = name;
+        require!(expense_manager.validate(), SlideError::DataTooLarge);
+        expense_manager.membership_token_mint = squad.mint_address;
+        expense_manager.namespace = Some(squad.key());
+        expense_manager.version = ExpenseManager::VERSION;
+        expense_manager.bump = *ctx
+            .bumps
+            .get("expense_manager")
+            .ok_or(SlideError::BumpNotFound)?;
+        expense_manager.external_program_id = external_program_id;
+        expense_manager.squad = Some(squad.key());
+        expense_manager.min_proposal_index =
+            previous_proposal_watermark(proposal_watermark, ctx.program_id)?;
+
+        let mut audit_log = ctx.accounts.audit_log.load_init()?;
+        audit_log.expense_manager = expense_manager.key();
+        audit_log.bump = *ctx.bumps.get("audit_log").ok_or(SlideError::BumpNotFound)?;
+
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        manager_stats.expense_manager = expense_manager.key();
+        manager_stats.bump = *ctx
+            .bumps
+            .get("manager_stats")
+            .ok_or(SlideError::BumpNotFound)?;
+        manager_stats.version = ExpenseManagerStats::VERSION;
+        manager_stats.counts_all_packages = true;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(ExpenseManagerCreated {
+            expense_manager: expense_manager.key(),
+            namespace: expense_manager.namespace,
+            name: expense_manager.name.clone(),
+            membership_token_mint: expense_manager.membership_token_mint,
+            payer: member.key(),
+            timestamp,
+        });
+        emit!(ExpenseManagerInitialized {
+            expense_manager: expense_manager.key(),
+            external_program_id,
+            realm: None,
+            governance_authority: None,
+            squad: expense_manager.squad,
+            member: member.key(),
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_create_expense_package(
+        ctx: Context<SquadsCreateExpensePackage>,
+        nonce: u32,
+        name: String,
+        description: String,
+        quantity: u64,
+        category: Option<u8>,
+    ) -> Result<()> {
+        let owner = &ctx.accounts.owner;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let user_data = &mut ctx.accounts.user_data;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_package.bump = *ctx
+            .bumps
+            .get("expense_package")
+            .ok_or(SlideError::BumpNotFound)?;
+        expense_package.expense_manager = expense_manager.key();
+        expense_package.owner = owner.key();
+        expense_package.name = name;
+        expense_package.description = description;
+        require!(expense_package.validate(), SlideError::DataTooLarge);
+        expense_package.quantity = quantity;
+        expense_package.nonce = nonce;
+        expense_package.category = category;
+        expense_package.version = ExpensePackage::VERSION;
+        manager_stats.move_package(None, &ExpensePackageState::Created)?;
+
+        if user_data.user == Pubkey::default() {
+            user_data.bump = *ctx.bumps.get("user_data").ok_or(SlideError::BumpNotFound)?;
+            user_data.user = owner.key();
+            user_data.version = UserData::VERSION;
+        }
+        user_data.expense_package_nonce =
+            nonce.checked_add(1).ok_or(SlideError::ArithmeticOverflow)?;
+
+        emit!(ExpensePackageCreated {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            owner: owner.key(),
+            nonce,
+            quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_update_expense_package(
+        ctx: Context<SquadsUpdateExpensePackage>,
+        _nonce: u32,
+        name: String,
+        description: String,
+        quantity: u64,
+    ) -> Result<()> {
+        let expense_package = &mut ctx.accounts.expense_package;
+
+        expense_package.name = name;
+        expense_package.description = description;
+        require!(expense_package.validate(), SlideError::DataTooLarge);
+        if expense_package.line_items.is_empty() {
+            expense_package.quantity = quantity;
+        } else {
+            require!(
+                quantity == expense_package.quantity,
+                SlideError::QuantityDerivedFromLineItems
+            );
+        }
+
+        emit!(ExpensePackageUpdated {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: expense_package.owner,
+            quantity: expense_package.quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_submit_expense_package(
+        ctx: Context<SquadsSubmitExpensePackage>,
+        _nonce: u32,
+        payee: Option<Pubkey>,
+    ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let expense_package = &mut ctx.accounts.expense_package;
+        let owner = &ctx.accounts.owner;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_package.payee = Some(payee.unwrap_or_else(|| owner.key()));
+        expense_package.state = ExpensePackageState::Pending;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Created),
+            &ExpensePackageState::Pending,
+        )?;
+        manager_stats.record_submission(expense_package.quantity)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        expense_package.submitted_at = timestamp;
+        audit_log.load_mut()?.append(
+            owner.key(),
+            AuditAction::PackageSubmitted,
+            expense_package.key(),
+            expense_package.quantity,
+            timestamp,
+        );
+        emit!(ExpensePackageSubmitted {
+            expense_manager: expense_package.expense_manager,
+            expense_package: expense_package.key(),
+            owner: owner.key(),
+            payee: expense_package.payout_address(),
+            quantity: expense_package.quantity,
+            timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_execute_access_proposal(ctx: Context<SquadsExecuteAccessProposal>) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let member = &ctx.accounts.member;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let access_record = &mut ctx.accounts.access_record;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
+
         // description
         // first line:
         //   starts with "member: "
@@ -346,10 +1862,6 @@ pub mod slide {
         // second line:
         //   starts with "role: "
         //   rest of line should match "reviewer" or "admin"
-        if !proposal.title.starts_with("[SLIDE PROPOSAL]") {
-            return err!(SlideError::FailedToParseProposal);
-        }
-
         let mut description_lines = proposal.description.lines();
         let first_line = description_lines
             .next()
@@ -374,181 +1886,681 @@ pub mod slide {
             _ => return err!(SlideError::FailedToParseProposal),
         };
 
-        access_record.bump = *ctx.bumps.get("access_record").unwrap();
+        access_record.bump = *ctx
+            .bumps
+            .get("access_record")
+            .ok_or(SlideError::BumpNotFound)?;
         access_record.user = member_pubkey;
         access_record.expense_manager = expense_manager.key();
         access_record.role = role;
+        access_record.version = AccessRecord::VERSION;
+        expense_manager.access_record_count = expense_manager
+            .access_record_count
+            .checked_add(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
 
         proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
         let clock = Clock::get()?;
         proposal_execution.executed_at = clock.unix_timestamp;
 
+        audit_log.load_mut()?.append(
+            signer.key(),
+            AuditAction::AccessGranted,
+            member_pubkey,
+            0,
+            clock.unix_timestamp,
+        );
+        emit!(AccessGranted {
+            expense_manager: expense_manager.key(),
+            user: member_pubkey,
+            role: access_record.role.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
-    pub fn squads_execute_withdrawal_proposal(
-        ctx: Context<SquadsExecuteWithdrawalProposal>,
+    pub fn squads_execute_metadata_proposal(
+        ctx: Context<SquadsExecuteMetadataProposal>,
     ) -> Result<()> {
         let proposal = &ctx.accounts.proposal;
         let proposal_execution = &mut ctx.accounts.proposal_execution;
         let squad = &ctx.accounts.squad;
         let squad_mint = &ctx.accounts.squad_mint;
-        let squad_treasury = &ctx.accounts.squad_treasury;
         let expense_manager = &ctx.accounts.expense_manager;
+        let manager_metadata = &mut ctx.accounts.manager_metadata;
+        let signer = &ctx.accounts.signer;
 
-        // TODO: move vote logic, validation logic elsewhere
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-        if proposal.votes_num != 2 {
-            return err!(SlideError::InvalidProposal);
-        }
+        // description
+        // first line:
+        //   starts with "manager: "
+        //   rest of line should parse to a publickey matching expense_manager
+        // following lines, in order:
+        //   "name: ", "description: ", "logo: ", "contact: "
+        //   rest of each line is the new value (may be empty)
+        let mut description_lines = proposal.description.lines();
+        let manager_pubkey =
+            Pubkey::from_str(next_proposal_field(&mut description_lines, "manager: ")?)
+                .map_err(|_| SlideError::FailedToParseProposal)?;
+        require!(
+            manager_pubkey == expense_manager.key(),
+            SlideError::InvalidProposal
+        );
+        let display_name = next_proposal_field(&mut description_lines, "name: ")?;
+        let description = next_proposal_field(&mut description_lines, "description: ")?;
+        let logo_uri = next_proposal_field(&mut description_lines, "logo: ")?;
+        let contact = next_proposal_field(&mut description_lines, "contact: ")?;
 
-        if proposal.votes_labels.get(0).unwrap().trim_end() != "Approve"
-            || proposal.votes_labels.get(1).unwrap().trim_end() != "Deny"
-        {
-            return err!(SlideError::InvalidProposal);
-        }
+        manager_metadata.display_name = display_name.to_string();
+        manager_metadata.description = description.to_string();
+        manager_metadata.logo_uri = logo_uri.to_string();
+        manager_metadata.contact = contact.to_string();
+        require!(manager_metadata.validate(), SlideError::DataTooLarge);
 
-        let pass_votes = *proposal.votes.get(0).unwrap();
-        let fail_votes = *proposal.votes.get(1).unwrap();
-        if pass_votes < fail_votes {
-            return err!(SlideError::InvalidProposal);
-        }
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        let clock = Clock::get()?;
+        proposal_execution.executed_at = clock.unix_timestamp;
 
-        // check quorum & support
-        let curr_quorum_percent;
-        let current_support_percent;
-        if proposal.execute_ready {
-            curr_quorum_percent =
-                (proposal.has_voted.len() as f32 / proposal.members_at_execute as f32) * 100.0;
+        emit!(ExpenseManagerMetadataUpdated {
+            expense_manager: expense_manager.key(),
+            display_name: manager_metadata.display_name.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
 
-            current_support_percent =
-                (pass_votes as f32 / proposal.supply_at_execute as f32) * 100.0;
-        } else {
-            curr_quorum_percent =
-                (proposal.has_voted.len() as f32 / squad.members.len() as f32) * 100.0;
+        Ok(())
+    }
+    pub fn squads_execute_unpause_proposal(
+        ctx: Context<SquadsExecuteManagerProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let signer = &ctx.accounts.signer;
 
-            current_support_percent = (pass_votes as f32 / squad_mint.supply as f32) * 100.0;
-        }
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-        if curr_quorum_percent < squad.vote_quorum as f32 {
-            return err!(SlideError::InvalidProposal);
-        }
+        // description
+        // first line:
+        //   starts with "unpause manager: "
+        //   rest of line should parse to a publickey matching expense_manager
+        let mut description_lines = proposal.description.lines();
+        let manager_pubkey = Pubkey::from_str(next_proposal_field(
+            &mut description_lines,
+            "unpause manager: ",
+        )?)
+        .map_err(|_| SlideError::FailedToParseProposal)?;
+        require!(
+            manager_pubkey == expense_manager.key(),
+            SlideError::InvalidProposal
+        );
 
-        if current_support_percent < squad.vote_support as f32 {
-            return err!(SlideError::InvalidProposal);
-        }
+        expense_manager.paused = false;
+
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        let clock = Clock::get()?;
+        proposal_execution.executed_at = clock.unix_timestamp;
+
+        emit!(ExpenseManagerUnpaused {
+            expense_manager: expense_manager.key(),
+            authority: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_execute_execution_window_proposal(
+        ctx: Context<SquadsExecuteManagerProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-        // title should start with [SLIDE PROPOSAL]
         // description
         // first line:
-        //   starts with "lamports: "
-        //   rest of line should parse to a number of lamports <= (manager - rent exempt)
-        // second line:
-        //   starts with "manager: "
+        //   starts with "set execution window: "
         //   rest of line should parse to a publickey matching expense_manager
-        // third line:
-        //   starts with "treasury: "
-        //   rest of line should parse to a publickey matching squad_treasury
-        if !proposal.title.starts_with("[SLIDE PROPOSAL]") {
-            return err!(SlideError::FailedToParseProposal);
-        }
+        // second line:
+        //   starts with "seconds: "
+        //   rest of line should parse to a number of seconds, or "none" for no limit
+        let mut description_lines = proposal.description.lines();
+        let manager_pubkey = Pubkey::from_str(next_proposal_field(
+            &mut description_lines,
+            "set execution window: ",
+        )?)
+        .map_err(|_| SlideError::FailedToParseProposal)?;
+        require!(
+            manager_pubkey == expense_manager.key(),
+            SlideError::InvalidProposal
+        );
+        let proposal_execution_window =
+            parse_optional_seconds(next_proposal_field(&mut description_lines, "seconds: ")?)?;
+
+        expense_manager.set_proposal_execution_window(proposal_execution_window)?;
+
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        let clock = Clock::get()?;
+        proposal_execution.executed_at = clock.unix_timestamp;
+
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_execute_withdrawal_timelock_proposal(
+        ctx: Context<SquadsExecuteManagerProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let signer = &ctx.accounts.signer;
 
+        let clock = Clock::get()?;
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            clock.unix_timestamp,
+        )?;
+
+        // description
+        // first line:
+        //   starts with "set withdrawal timelock: "
+        //   rest of line should parse to a publickey matching expense_manager
+        // second line:
+        //   starts with "seconds: "
+        //   rest of line should parse to a number of seconds, or "none" to remove it
         let mut description_lines = proposal.description.lines();
-        let first_line = description_lines
-            .next()
-            .ok_or(SlideError::FailedToParseProposal)?;
+        let manager_pubkey = Pubkey::from_str(next_proposal_field(
+            &mut description_lines,
+            "set withdrawal timelock: ",
+        )?)
+        .map_err(|_| SlideError::FailedToParseProposal)?;
         require!(
-            first_line.starts_with("lamports: "),
-            SlideError::FailedToParseProposal
+            manager_pubkey == expense_manager.key(),
+            SlideError::InvalidProposal
         );
-        let withdraw_lamports = first_line[10..]
-            .trim_end()
-            .parse::<u64>()
-            .map_err(|_| SlideError::FailedToParseProposal)?;
-        let second_line = description_lines
-            .next()
-            .ok_or(SlideError::FailedToParseProposal)?;
+        let withdrawal_timelock =
+            parse_optional_seconds(next_proposal_field(&mut description_lines, "seconds: ")?)?;
+
+        expense_manager.set_withdrawal_timelock(withdrawal_timelock, clock.unix_timestamp)?;
+
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        proposal_execution.executed_at = clock.unix_timestamp;
+
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_execute_recurring_expense_proposal(
+        ctx: Context<SquadsExecuteRecurringExpenseProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let recurring_expense = &mut ctx.accounts.recurring_expense;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // description
+        // first line:
+        //   starts with "approve recurring expense: "
+        //   rest of line should parse to a publickey matching recurring_expense
+        let mut description_lines = proposal.description.lines();
+        let recurring_expense_pubkey = Pubkey::from_str(next_proposal_field(
+            &mut description_lines,
+            "approve recurring expense: ",
+        )?)
+        .map_err(|_| SlideError::FailedToParseProposal)?;
         require!(
-            second_line.starts_with("manager: "),
-            SlideError::FailedToParseProposal
+            recurring_expense_pubkey == recurring_expense.key(),
+            SlideError::InvalidProposal
         );
-        let manager_pubkey = Pubkey::from_str(second_line[9..].trim_end())
-            .map_err(|_| SlideError::FailedToParseProposal)?;
+
+        let clock = Clock::get()?;
+        recurring_expense.approve(squad.key(), clock.unix_timestamp);
+
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        proposal_execution.executed_at = clock.unix_timestamp;
+
+        emit!(RecurringExpenseApproved {
+            expense_manager: expense_manager.key(),
+            recurring_expense: recurring_expense.key(),
+            approved_by: squad.key(),
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_execute_close_proposal(
+        ctx: Context<SquadsExecuteCloseProposal>,
+        force_deny_nonces: Vec<u32>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let squad_treasury = &ctx.accounts.squad_treasury;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        let proposal_watermark = &mut ctx.accounts.proposal_watermark;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // description
+        // first line:
+        //   starts with "close manager: "
+        //   rest of line should parse to a publickey matching expense_manager
+        let mut description_lines = proposal.description.lines();
+        let manager_pubkey = Pubkey::from_str(next_proposal_field(
+            &mut description_lines,
+            "close manager: ",
+        )?)
+        .map_err(|_| SlideError::FailedToParseProposal)?;
         require!(
             manager_pubkey == expense_manager.key(),
-            SlideError::FailedToParseProposal
+            SlideError::InvalidProposal
         );
-        let third_line = description_lines
-            .next()
-            .ok_or(SlideError::FailedToParseProposal)?;
-        require!(
-            third_line.starts_with("treasury: "),
-            SlideError::FailedToParseProposal
+
+        // the watermark outlives the manager, so one re-created at this address can't
+        // replay proposals whose execution records were already closed
+        proposal_watermark.bump = *ctx
+            .bumps
+            .get("proposal_watermark")
+            .ok_or(SlideError::BumpNotFound)?;
+        proposal_watermark.min_proposal_index = proposal_watermark
+            .min_proposal_index
+            .max(expense_manager.min_proposal_index);
+        proposal_watermark.version = ProposalWatermark::VERSION;
+
+        let squad_treasury_info = squad_treasury.to_account_info();
+        settle_expense_manager(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &ctx.accounts.manager_metadata.to_account_info(),
+            &signer.key(),
+            &squad_treasury_info,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            &force_deny_nonces,
+            ctx.program_id,
+        )?;
+
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        let clock = Clock::get()?;
+        proposal_execution.executed_at = clock.unix_timestamp;
+
+        // the remaining lamports are swept to the treasury when the manager is closed
+        let amount = expense_manager.to_account_info().lamports();
+        audit_log.append(
+            signer.key(),
+            AuditAction::ManagerClosed,
+            squad_treasury.key(),
+            amount,
+            clock.unix_timestamp,
         );
-        let treasury_pubkey = Pubkey::from_str(third_line[10..].trim_end())
-            .map_err(|_| SlideError::FailedToParseProposal)?;
+        emit!(ExpenseManagerClosed {
+            expense_manager: expense_manager.key(),
+            treasury: squad_treasury.key(),
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_close_proposal_execution(
+        ctx: Context<SquadsCloseProposalExecution>,
+        proposal_index: u32,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+
+        let now = Clock::get()?.unix_timestamp;
+        let proposal_closed =
+            proposal.data_is_empty() || *proposal.owner != expense_manager.external_program_id;
+        let retention_elapsed = now
+            >= proposal_execution
+                .executed_at
+                .saturating_add(ProposalExecution::RETENTION_PERIOD);
         require!(
-            treasury_pubkey == squad_treasury.key(),
-            SlideError::FailedToParseProposal
+            proposal_closed || retention_elapsed,
+            SlideError::ProposalExecutionRetained
         );
 
+        // once the record is gone, the index watermark stops the proposal being replayed,
+        // but it may only pass lower proposals which can no longer be executed
+        check_lower_proposals_settled(
+            expense_manager,
+            &squad.key(),
+            proposal_index,
+            ctx.remaining_accounts,
+            now,
+            ctx.program_id,
+        )?;
+        let next_index = proposal_index
+            .checked_add(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+        expense_manager.min_proposal_index = expense_manager.min_proposal_index.max(next_index);
+
+        Ok(())
+    }
+    pub fn squads_execute_withdrawal_proposal(
+        ctx: Context<SquadsExecuteWithdrawalProposal>,
+    ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let squad_treasury = &ctx.accounts.squad_treasury;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let withdraw_lamports =
+            parse_withdrawal_proposal(proposal, &expense_manager.key(), &squad_treasury.key())?;
+
         let manager_info = expense_manager.to_account_info();
         let squad_treasury_info = squad_treasury.to_account_info();
 
-        let mut manager_balance = manager_info.try_borrow_mut_lamports()?;
-        let mut squad_treasury_balance = squad_treasury_info.try_borrow_mut_lamports()?;
+        pay_from_manager(&manager_info, &squad_treasury_info, withdraw_lamports)?;
+        manager_stats.record_withdrawal(withdraw_lamports)?;
 
-        // need to determine manager balance aside from rent-exemption
-        let rent = Rent::get()?;
-        let rent_exempt_lamports = rent.minimum_balance(manager_info.data_len()).max(1);
+        proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
+        let clock = Clock::get()?;
+        proposal_execution.executed_at = clock.unix_timestamp;
 
-        let max_withdrawal_amount = manager_balance.checked_sub(rent_exempt_lamports).unwrap();
-        require!(
-            withdraw_lamports <= max_withdrawal_amount,
-            SlideError::ManagerInsufficientFunds
+        audit_log.load_mut()?.append(
+            signer.key(),
+            AuditAction::ManagerWithdrawal,
+            squad_treasury.key(),
+            withdraw_lamports,
+            clock.unix_timestamp,
         );
+        emit!(ExpenseManagerWithdrawal {
+            expense_manager: expense_manager.key(),
+            treasury: squad_treasury.key(),
+            amount: withdraw_lamports,
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+    pub fn squads_queue_withdrawal_proposal(
+        ctx: Context<SquadsQueueWithdrawalProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let proposal_execution = &mut ctx.accounts.proposal_execution;
+        let withdrawal_request = &mut ctx.accounts.withdrawal_request;
+        let squad = &ctx.accounts.squad;
+        let squad_mint = &ctx.accounts.squad_mint;
+        let squad_treasury = &ctx.accounts.squad_treasury;
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let signer = &ctx.accounts.signer;
+
+        check_squads_proposal_passed(proposal, squad, squad_mint)?;
+        check_squads_proposal_timing(
+            proposal,
+            expense_manager.proposal_execution_window,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-        **manager_balance = manager_balance.checked_sub(withdraw_lamports).unwrap();
-        **squad_treasury_balance = squad_treasury_balance
-            .checked_add(withdraw_lamports)
-            .unwrap();
+        let withdraw_lamports =
+            parse_withdrawal_proposal(proposal, &expense_manager.key(), &squad_treasury.key())?;
 
         proposal_execution.proposal = proposal.key();
+        proposal_execution.version = ProposalExecution::VERSION;
+        proposal_execution.executed_by = Some(signer.key());
         let clock = Clock::get()?;
         proposal_execution.executed_at = clock.unix_timestamp;
 
+        withdrawal_request.bump = *ctx
+            .bumps
+            .get("withdrawal_request")
+            .ok_or(SlideError::BumpNotFound)?;
+        withdrawal_request.expense_manager = expense_manager.key();
+        withdrawal_request.destination = squad_treasury.key();
+        withdrawal_request.amount = withdraw_lamports;
+        withdrawal_request.requested_at = clock.unix_timestamp;
+        withdrawal_request.executable_at = clock
+            .unix_timestamp
+            .saturating_add(expense_manager.withdrawal_timelock.unwrap_or(0) as i64);
+        withdrawal_request.payer = signer.key();
+        withdrawal_request.version = WithdrawalRequest::VERSION;
+        expense_manager.withdrawal_request_count = expense_manager
+            .withdrawal_request_count
+            .checked_add(1)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+
+        emit!(WithdrawalRequested {
+            expense_manager: expense_manager.key(),
+            withdrawal_request: withdrawal_request.key(),
+            destination: squad_treasury.key(),
+            amount: withdraw_lamports,
+            executable_at: withdrawal_request.executable_at,
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(ProposalExecuted {
+            expense_manager: expense_manager.key(),
+            proposal: proposal.key(),
+            executed_by: signer.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
     pub fn squads_approve_expense_package(
         ctx: Context<SquadsApproveExpensePackage>,
         _nonce: u32,
+        approved_amount: Option<u64>,
+        vesting: Option<VestingSchedule>,
     ) -> Result<()> {
         let expense_package = &mut ctx.accounts.expense_package;
         let expense_manager = &mut ctx.accounts.expense_manager;
-
-        expense_package.state = ExpensePackageState::Approved;
-
-        let package_info = expense_package.to_account_info();
-        let mut package_balance = package_info.try_borrow_mut_lamports()?;
-        let manager_info = expense_manager.to_account_info();
-        let mut manager_balance = manager_info.try_borrow_mut_lamports()?;
-
-        **package_balance = package_balance
-            .checked_add(expense_package.quantity)
-            .unwrap();
-        **manager_balance = manager_balance
-            .checked_sub(expense_package.quantity)
-            .unwrap();
-
-        Ok(())
+        let authority = &ctx.accounts.authority;
+        let payee = &ctx.accounts.payee;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        approve_expense_package(
+            expense_package,
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            payee,
+            approved_amount,
+            vesting,
+        )
     }
     pub fn squads_deny_expense_package(
-        ctx: Context<SquadsApproveExpensePackage>,
+        ctx: Context<SquadsDenyExpensePackage>,
         _nonce: u32,
     ) -> Result<()> {
+        let audit_log = &ctx.accounts.audit_log;
         let expense_package = &mut ctx.accounts.expense_package;
+        let expense_manager = &ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        expense_manager.check_category_reviewer(expense_package.category, &authority.key())?;
 
         expense_package.state = ExpensePackageState::Denied;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Pending),
+            &ExpensePackageState::Denied,
+        )?;
+        manager_stats.record_denial(expense_package.quantity)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        audit_log.load_mut()?.append(
+            authority.key(),
+            AuditAction::PackageDenied,
+            expense_package.key(),
+            0,
+            timestamp,
+        );
+        emit!(ExpensePackageDenied {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            reviewer: authority.key(),
+            timestamp,
+        });
 
         Ok(())
     }
+    pub fn squads_approve_expense_packages(
+        ctx: Context<SquadsReviewExpensePackages>,
+        nonces: Vec<u32>,
+        approved_amounts: Vec<Option<u64>>,
+    ) -> Result<()> {
+        let expense_manager = &mut ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        require!(
+            !ctx.accounts.squad.emergency_lock,
+            SlideError::SquadEmergencyLock
+        );
+        approve_expense_package_batch(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            ctx.remaining_accounts,
+            &nonces,
+            &approved_amounts,
+            ctx.program_id,
+        )
+    }
+    pub fn squads_deny_expense_packages(
+        ctx: Context<SquadsReviewExpensePackages>,
+        nonces: Vec<u32>,
+    ) -> Result<()> {
+        let expense_manager = &ctx.accounts.expense_manager;
+        let authority = &ctx.accounts.authority;
+        let mut audit_log = ctx.accounts.audit_log.load_mut()?;
+        let manager_stats = &mut ctx.accounts.manager_stats;
+
+        deny_expense_package_batch(
+            expense_manager,
+            &mut audit_log,
+            manager_stats,
+            &authority.key(),
+            ctx.remaining_accounts,
+            &nonces,
+            ctx.program_id,
+        )
+    }
 }
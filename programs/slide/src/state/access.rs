@@ -1,3 +1,4 @@
+use crate::utils::{VersionedAccount, VERSION_FIELDS_SIZE};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -7,15 +8,31 @@ pub struct AccessRecord {
     pub user: Pubkey,
     pub expense_manager: Pubkey,
     pub role: Role,
+    pub version: u8,
+    pub reserved: [u8; 32],
 }
 
-// TODO: reserve more space for future changes (also applies to other structs tbh)
 impl AccessRecord {
+    pub const VERSION: u8 = 1;
+
     // bump: 1
     // user: 32
     // expense_manager: 32
     // role: 1
-    pub const MAX_SIZE: usize = 1 + 32 + 32 + 1;
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize = 1 + 32 + 32 + 1 + 1 + 32;
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] = [0, VERSION_FIELDS_SIZE];
+}
+
+impl VersionedAccount for AccessRecord {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -29,6 +46,9 @@ impl Role {
         // currently all Roles can approve and deny
         true
     }
+    pub fn can_manage(&self) -> bool {
+        matches!(self, Role::Admin)
+    }
 }
 
 impl Default for Role {
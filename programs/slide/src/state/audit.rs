@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+/// Fixed-capacity ring buffer of the most recent actions on an ExpenseManager.
+/// Drafting actions (creating and editing packages) are not logged so that package
+/// creation does not contend on this account.
+#[account(zero_copy)]
+pub struct AuditLog {
+    pub expense_manager: Pubkey,
+    // total entries ever appended, the newest entry is at (count - 1) % CAPACITY
+    pub count: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub entries: [AuditLogEntry; 64],
+}
+
+impl AuditLog {
+    pub const CAPACITY: usize = 64;
+
+    // expense_manager: 32
+    // count: 8
+    // bump: 1
+    // _padding: 7
+    // entries: 64 * AuditLogEntry::SIZE
+    pub const SIZE: usize = 32 + 8 + 1 + 7 + Self::CAPACITY * AuditLogEntry::SIZE;
+
+    pub fn append(
+        &mut self,
+        actor: Pubkey,
+        action: AuditAction,
+        subject: Pubkey,
+        amount: u64,
+        timestamp: i64,
+    ) {
+        let index = (self.count % Self::CAPACITY as u64) as usize;
+        self.entries[index] = AuditLogEntry {
+            actor,
+            subject,
+            amount,
+            timestamp,
+            action: action as u8,
+            _padding: [0; 7],
+        };
+        self.count = self.count.wrapping_add(1);
+    }
+}
+
+#[zero_copy]
+pub struct AuditLogEntry {
+    pub actor: Pubkey,
+    // the ExpensePackage (or RecurringExpense) for package actions, the granted user
+    // for access grants, and the funder or treasury for manager funding, withdrawals
+    // and closing
+    pub subject: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64, // UnixTimestamp
+    pub action: u8,     // AuditAction
+    pub _padding: [u8; 7],
+}
+
+impl AuditLogEntry {
+    // actor: 32
+    // subject: 32
+    // amount: 8
+    // timestamp: 8
+    // action: 1
+    // _padding: 7
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 1 + 7;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    ManagerFunded,
+    ManagerWithdrawal,
+    AccessGranted,
+    PackageSubmitted,
+    PackageApproved,
+    PackageDenied,
+    PackagePaid,
+    ManagerClosed,
+    PackageExpired,
+    RecurringExpensePaid,
+    AdvanceReconciled,
+    AdvanceRejected,
+}
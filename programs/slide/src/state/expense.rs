@@ -1,3 +1,4 @@
+use crate::utils::{SlideError, VersionedAccount, VERSION_FIELDS_SIZE};
 use crate::GovernanceType;
 use anchor_lang::prelude::*;
 
@@ -7,17 +8,53 @@ pub struct ExpenseManager {
     pub bump: u8,
     pub name: String,
     pub membership_token_mint: Pubkey,
+    // no longer used, package nonces are tracked per owner on UserData
     pub expense_package_nonce: u32,
     pub squad: Option<Pubkey>,
     pub realm: Option<Pubkey>,
     pub governance_authority: Option<Pubkey>,
     pub external_program_id: Pubkey,
     pub governance_type: Option<GovernanceType>,
+    pub require_attachments: bool,
+    pub categories: Vec<ExpenseCategory>,
+    pub direct_payout: bool,
+    pub version: u8,
+    pub reserved: [u8; 32],
+    // realm, squad or creator the manager name is scoped to, None for legacy managers
+    pub namespace: Option<Pubkey>,
+    // set by an Admin in an emergency, only governance can clear it
+    pub paused: bool,
+    // seconds after a Squads proposal closes (or is executed in Squads) during which
+    // it can still be executed through Slide, None for no limit. Changed by proposal only
+    pub proposal_execution_window: Option<u32>,
+    // Squads proposals below this index can no longer be executed, raised as their
+    // ProposalExecution records are closed
+    pub min_proposal_index: u32,
+    // seconds between governance approving a withdrawal and it being executable,
+    // None to withdraw immediately. Changed by governance only
+    pub withdrawal_timelock: Option<u32>,
+    // seconds a package may stay Pending before it is escalated, and before it expires
+    pub review_sla: Option<u32>,
+    pub max_pending_age: Option<u32>,
+    // seconds an Approved package has to be withdrawn before its funds return to the manager
+    pub withdrawal_deadline: Option<u32>,
+    // live AccessRecords, which must all be closed before the manager is
+    pub access_record_count: u32,
+    // a lower timelock (0 to remove it) which applies once the current one has passed
+    pub pending_withdrawal_timelock: Option<u32>,
+    pub pending_timelock_effective_at: i64, // UnixTimestamp
+    // queued WithdrawalRequests, which must all be executed or cancelled before the
+    // manager is closed
+    pub withdrawal_request_count: u32,
 }
 
 impl ExpenseManager {
+    // the name is also a PDA seed, and seeds are limited to 32 bytes
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const VERSION: u8 = 9;
+
     // bump: 1
-    // name: 64
+    // name: 4 + 32
     // membership_token_mint: 32
     // expense_package_nonce: 4
     // squad: 33
@@ -25,7 +62,163 @@ impl ExpenseManager {
     // governance_authority: 33
     // external_program_id: 32
     // governance_type: 2
-    pub const MAX_SIZE: usize = 1 + 64 + 32 + 4 + 33 + 33 + 33 + 32 + 2;
+    // require_attachments: 1
+    // categories: 4 (grows by ExpenseCategory::MAX_SIZE per category via realloc)
+    // direct_payout: 1
+    // version: 1
+    // reserved: 32
+    // namespace: 33
+    // paused: 1
+    // proposal_execution_window: 5
+    // min_proposal_index: 4
+    // withdrawal_timelock: 5
+    // review_sla, max_pending_age, withdrawal_deadline: 3 * 5
+    // access_record_count: 4
+    // pending_withdrawal_timelock: 5
+    // pending_timelock_effective_at: 8
+    // withdrawal_request_count: 4
+    pub const MAX_SIZE: usize =
+        Self::UNVERSIONED_SIZE + VERSION_FIELDS_SIZE + 33 + 1 + 5 + 4 + 5 + 3 * 5 + 4 + 5 + 8 + 4;
+    // bump through direct_payout, written before layouts were versioned
+    const UNVERSIONED_SIZE: usize = 1 + 4 + 32 + 32 + 4 + 3 * 33 + 32 + 2 + 1 + 4 + 1;
+    // namespace, paused, proposal_execution_window, min_proposal_index,
+    // withdrawal_timelock, then review_sla, max_pending_age and withdrawal_deadline,
+    // then access_record_count, then the pending timelock and withdrawal_request_count
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] =
+        [0, VERSION_FIELDS_SIZE, 33, 1, 5, 4, 5, 3 * 5, 4, 5 + 8 + 4];
+    pub const MAX_CATEGORIES: usize = 16;
+    // shorter windows could expire proposals before anyone has a chance to execute them
+    pub const MIN_PROPOSAL_EXECUTION_WINDOW: u32 = 24 * 60 * 60;
+    // withdrawals and closing wait for the timelock, so it can't lock funds away for long
+    pub const MAX_WITHDRAWAL_TIMELOCK: u32 = 30 * 24 * 60 * 60;
+
+    pub fn validate(&self) -> bool {
+        self.name.len() <= Self::MAX_NAME_LEN
+    }
+
+    /// Account size (including discriminator) needed to hold the current categories
+    pub fn space(&self) -> usize {
+        8 + Self::MAX_SIZE + self.categories.len() * ExpenseCategory::MAX_SIZE
+    }
+
+    /// PDA seed for the namespace. Legacy managers use an empty seed, which derives
+    /// the same address as their original name-only seeds.
+    pub fn namespace_seed(&self) -> &[u8] {
+        match &self.namespace {
+            Some(namespace) => namespace.as_ref(),
+            None => &[],
+        }
+    }
+
+    pub fn set_proposal_execution_window(&mut self, window: Option<u32>) -> Result<()> {
+        require!(
+            window.map_or(true, |window| window >= Self::MIN_PROPOSAL_EXECUTION_WINDOW),
+            SlideError::ExecutionWindowTooShort
+        );
+        self.proposal_execution_window = window;
+        Ok(())
+    }
+
+    /// Raises the withdrawal timelock straight away. A lower one has to wait out the
+    /// current timelock, so it can't be lifted to rush a withdrawal through; setting it
+    /// again cancels a pending change.
+    pub fn set_withdrawal_timelock(&mut self, timelock: Option<u32>, now: i64) -> Result<()> {
+        let requested = timelock.unwrap_or(0);
+        require!(
+            requested <= Self::MAX_WITHDRAWAL_TIMELOCK,
+            SlideError::WithdrawalTimelockTooLong
+        );
+        let current = self.withdrawal_timelock.unwrap_or(0);
+        if requested >= current {
+            self.withdrawal_timelock = timelock;
+            self.pending_withdrawal_timelock = None;
+        } else {
+            self.pending_withdrawal_timelock = Some(requested);
+            self.pending_timelock_effective_at = now.saturating_add(current as i64);
+        }
+        Ok(())
+    }
+
+    pub fn has_category(&self, category: Option<u8>) -> bool {
+        match category {
+            Some(index) => (index as usize) < self.categories.len(),
+            None => true,
+        }
+    }
+
+    /// Packages in a category with assigned reviewers may only be reviewed by them
+    pub fn check_category_reviewer(&self, category: Option<u8>, reviewer: &Pubkey) -> Result<()> {
+        if let Some(index) = category {
+            let category = self
+                .categories
+                .get(index as usize)
+                .ok_or(SlideError::CategoryNotFound)?;
+            require!(
+                category.reviewers.is_empty() || category.reviewers.contains(reviewer),
+                SlideError::UserNotCategoryReviewer
+            );
+        }
+        Ok(())
+    }
+
+    /// Counts an approved amount against its category's budget, if it has one
+    pub fn record_category_spend(&mut self, category: Option<u8>, amount: u64) -> Result<()> {
+        if let Some(index) = category {
+            let category = self
+                .categories
+                .get_mut(index as usize)
+                .ok_or(SlideError::CategoryNotFound)?;
+            let spent = category
+                .spent
+                .checked_add(amount)
+                .ok_or(SlideError::ArithmeticOverflow)?;
+            if let Some(budget) = category.budget {
+                require!(spent <= budget, SlideError::CategoryBudgetExceeded);
+            }
+            category.spent = spent;
+        }
+        Ok(())
+    }
+
+    /// Gives back an approved amount which was never paid out to its category's budget
+    pub fn release_category_spend(&mut self, category: Option<u8>, amount: u64) {
+        if let Some(category) = category.and_then(|index| self.categories.get_mut(index as usize)) {
+            category.spent = category.spent.saturating_sub(amount);
+        }
+    }
+}
+
+impl VersionedAccount for ExpenseManager {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ExpenseCategory {
+    pub name: String,
+    pub budget: Option<u64>,
+    pub spent: u64,
+    pub reviewers: Vec<Pubkey>,
+}
+
+impl ExpenseCategory {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_REVIEWERS: usize = 8;
+
+    // name: 4 + 32
+    // budget: 9
+    // spent: 8
+    // reviewers: 4 + 8 * 32
+    pub const MAX_SIZE: usize = 4 + 32 + 9 + 8 + 4 + 8 * 32;
+
+    pub fn validate(&self) -> bool {
+        self.name.len() <= Self::MAX_NAME_LEN && self.reviewers.len() <= Self::MAX_REVIEWERS
+    }
 }
 
 #[account]
@@ -39,18 +232,251 @@ pub struct ExpensePackage {
     pub state: ExpensePackageState,
     pub quantity: u64,
     pub nonce: u32,
+    pub line_items: Vec<ExpenseLineItem>,
+    pub attachments: Vec<ExpenseAttachment>,
+    pub category: Option<u8>,
+    pub payee: Option<Pubkey>,
+    pub version: u8,
+    pub reserved: [u8; 32],
+    // zero for packages submitted or approved before these were tracked
+    pub submitted_at: i64, // UnixTimestamp
+    pub approved_at: i64,  // UnixTimestamp
+    pub escalated: bool,
+    // advances are paid up-front and reconciled against receipts afterwards
+    pub advance: bool,
+    pub reconciled_amount: Option<u64>,
+    // set when a reviewer approves less than the full quantity
+    pub approved_amount: Option<u64>,
+    // approved funds are released linearly over the schedule when set
+    pub vesting: Option<VestingSchedule>,
+    pub withdrawn_amount: u64,
+    // the manager's withdrawal_deadline when the package was approved, so shortening it
+    // later can't sweep packages early; None for packages approved before it was recorded
+    pub withdrawal_deadline: Option<u32>,
 }
 
 impl ExpensePackage {
+    pub const MAX_NAME_LEN: usize = 64;
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+    pub const VERSION: u8 = 6;
+
     // bump: 1
     // owner: 32
     // expense_manager: 32
-    // name: 64
-    // description: 256
+    // name: 4 + 64
+    // description: 4 + 256
     // state: 1
     // quantity: 8
     // nonce: 4
-    pub const MAX_SIZE: usize = 1 + 32 + 32 + 64 + 256 + 1 + 8 + 4;
+    // line_items: 4 (grows by ExpenseLineItem::MAX_SIZE per item via realloc)
+    // attachments: 4 (grows by ExpenseAttachment::MAX_SIZE per item via realloc)
+    // category: 2
+    // payee: 33
+    // version: 1
+    // reserved: 32
+    // submitted_at: 8
+    // approved_at: 8
+    // escalated: 1
+    // advance: 1
+    // reconciled_amount: 9
+    // approved_amount: 9
+    // vesting: 1 + 24
+    // withdrawn_amount: 8
+    // withdrawal_deadline: 5
+    pub const MAX_SIZE: usize =
+        Self::UNVERSIONED_SIZE + VERSION_FIELDS_SIZE + 8 + 8 + 1 + 1 + 9 + 9 + 25 + 8 + 5;
+    // bump through payee, written before layouts were versioned
+    const UNVERSIONED_SIZE: usize = 1 + 32 + 32 + 4 + 64 + 4 + 256 + 1 + 8 + 4 + 4 + 4 + 2 + 33;
+    // submitted_at, approved_at and escalated, then advance and reconciled_amount,
+    // approved_amount, then vesting and withdrawn_amount, then withdrawal_deadline
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] =
+        [0, VERSION_FIELDS_SIZE, 8 + 8 + 1, 1 + 9, 9, 25 + 8, 5];
+    pub const MAX_LINE_ITEMS: usize = 32;
+    pub const MAX_ATTACHMENTS: usize = 8;
+
+    pub fn validate(&self) -> bool {
+        self.name.len() <= Self::MAX_NAME_LEN && self.description.len() <= Self::MAX_DESCRIPTION_LEN
+    }
+
+    /// Account size (including discriminator) needed to hold the current line items
+    /// and attachments
+    pub fn space(&self) -> usize {
+        8 + Self::MAX_SIZE
+            + self.line_items.len() * ExpenseLineItem::MAX_SIZE
+            + self.attachments.len() * ExpenseAttachment::MAX_SIZE
+    }
+
+    /// Where reimbursement funds go; the owner unless a payee was set on submission
+    pub fn payout_address(&self) -> Pubkey {
+        self.payee.unwrap_or(self.owner)
+    }
+
+    /// Records how much of the quantity a reviewer approved, None approves all of it
+    pub fn set_approved_amount(&mut self, approved_amount: Option<u64>) -> Result<()> {
+        if let Some(amount) = approved_amount {
+            require!(
+                amount > 0 && amount <= self.quantity,
+                SlideError::InvalidApprovedAmount
+            );
+        }
+        self.approved_amount = approved_amount;
+        Ok(())
+    }
+
+    /// Amount moved out of the manager when the package was approved
+    pub fn approved_quantity(&self) -> u64 {
+        self.approved_amount.unwrap_or(self.quantity)
+    }
+
+    pub fn is_partially_approved(&self) -> bool {
+        self.approved_quantity() < self.quantity
+    }
+
+    /// Approved funds still held by the package
+    pub fn remaining_amount(&self) -> u64 {
+        self.approved_quantity()
+            .saturating_sub(self.withdrawn_amount)
+    }
+
+    /// When the approved funds are fully releasable, zero if approval was not tracked
+    pub fn fully_vested_at(&self) -> i64 {
+        self.vesting.as_ref().map_or(self.approved_at, |vesting| {
+            vesting.end.max(self.approved_at)
+        })
+    }
+
+    /// Marks the portion vested by `now` as withdrawn and returns it, moving the package
+    /// to Paid once the whole approved amount has been released
+    pub fn release(&mut self, now: i64) -> Result<u64> {
+        let total = self.approved_quantity();
+        let vested = self
+            .vesting
+            .as_ref()
+            .map_or(total, |vesting| vesting.vested_amount(total, now));
+        let amount = vested.saturating_sub(self.withdrawn_amount);
+        require!(amount > 0, SlideError::NothingVested);
+
+        self.withdrawn_amount = self
+            .withdrawn_amount
+            .checked_add(amount)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+        if self.withdrawn_amount == total {
+            self.state = ExpensePackageState::Paid;
+        }
+        Ok(amount)
+    }
+
+    /// Receipts can still be attached to a paid advance ahead of reconciling it
+    pub fn can_edit_attachments(&self) -> bool {
+        self.state == ExpensePackageState::Created
+            || (self.advance && self.state == ExpensePackageState::Paid)
+    }
+
+    pub fn line_items_total(&self) -> Option<u64> {
+        self.line_items
+            .iter()
+            .try_fold(0u64, |total, item| total.checked_add(item.amount))
+    }
+}
+
+impl VersionedAccount for ExpensePackage {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub start: i64, // UnixTimestamp
+    pub cliff: i64, // UnixTimestamp
+    pub end: i64,   // UnixTimestamp
+}
+
+impl VestingSchedule {
+    // start: 8
+    // cliff: 8
+    // end: 8
+    pub const SIZE: usize = 8 + 8 + 8;
+
+    pub fn validate(&self) -> bool {
+        self.start <= self.cliff && self.cliff <= self.end && self.start < self.end
+    }
+
+    /// Portion of `total` vested by `now`, nothing before the cliff and then linear from start
+    pub fn vested_amount(&self, total: u64, now: i64) -> u64 {
+        if now < self.cliff {
+            0
+        } else if now >= self.end {
+            total
+        } else {
+            let elapsed = (now - self.start) as u128;
+            let duration = (self.end - self.start) as u128;
+            (total as u128 * elapsed / duration) as u64
+        }
+    }
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ExpenseLineItem {
+    pub description: String,
+    pub amount: u64,
+    pub category: String,
+    pub date: i64, // UnixTimestamp
+}
+
+impl ExpenseLineItem {
+    pub const MAX_DESCRIPTION_LEN: usize = 128;
+    pub const MAX_CATEGORY_LEN: usize = 32;
+
+    // description: 4 + 128
+    // amount: 8
+    // category: 4 + 32
+    // date: 8
+    pub const MAX_SIZE: usize = 4 + 128 + 8 + 4 + 32 + 8;
+
+    pub fn validate(&self) -> bool {
+        self.description.len() <= Self::MAX_DESCRIPTION_LEN
+            && self.category.len() <= Self::MAX_CATEGORY_LEN
+    }
+}
+
+/// Reference to an off-chain receipt. Reviewers can fetch the file from `uri`
+/// and verify it against `content_hash` (sha256 of the file contents).
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ExpenseAttachment {
+    pub scheme: UriScheme,
+    pub content_hash: [u8; 32],
+    pub uri: String,
+}
+
+impl ExpenseAttachment {
+    pub const MAX_URI_LEN: usize = 128;
+
+    // scheme: 1
+    // content_hash: 32
+    // uri: 4 + 128
+    pub const MAX_SIZE: usize = 1 + 32 + 4 + 128;
+
+    pub fn validate(&self) -> bool {
+        self.uri.len() <= Self::MAX_URI_LEN
+    }
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum UriScheme {
+    Ipfs,
+    Arweave,
+    Https,
+}
+
+impl Default for UriScheme {
+    fn default() -> Self {
+        Self::Ipfs
+    }
 }
 
 #[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -61,6 +487,9 @@ pub enum ExpensePackageState {
     Approved,
     AutoApproved,
     Paid,
+    Expired,
+    Reconciling,
+    Reconciled,
 }
 
 impl Default for ExpensePackageState {
@@ -68,3 +497,194 @@ impl Default for ExpensePackageState {
         Self::Created
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(quantity: u64) -> ExpensePackage {
+        ExpensePackage {
+            quantity,
+            state: ExpensePackageState::Approved,
+            ..ExpensePackage::default()
+        }
+    }
+
+    #[test]
+    fn approves_the_whole_quantity_by_default() {
+        let mut expense_package = package(100);
+        expense_package.set_approved_amount(None).unwrap();
+        assert_eq!(expense_package.approved_quantity(), 100);
+        assert!(!expense_package.is_partially_approved());
+    }
+
+    #[test]
+    fn approves_part_of_the_quantity() {
+        let mut expense_package = package(100);
+        expense_package.set_approved_amount(Some(40)).unwrap();
+        assert_eq!(expense_package.approved_quantity(), 40);
+        assert!(expense_package.is_partially_approved());
+    }
+
+    #[test]
+    fn rejects_approved_amounts_outside_the_quantity() {
+        let mut expense_package = package(100);
+        for amount in [0, 101] {
+            assert_eq!(
+                expense_package.set_approved_amount(Some(amount)),
+                Err(SlideError::InvalidApprovedAmount.into())
+            );
+        }
+        assert_eq!(expense_package.approved_amount, None);
+    }
+
+    const VESTING: VestingSchedule = VestingSchedule {
+        start: 100,
+        cliff: 150,
+        end: 200,
+    };
+
+    #[test]
+    fn vests_nothing_before_the_cliff() {
+        assert_eq!(VESTING.vested_amount(1_000, 99), 0);
+        assert_eq!(VESTING.vested_amount(1_000, 149), 0);
+    }
+
+    #[test]
+    fn vests_linearly_from_the_start_after_the_cliff() {
+        assert_eq!(VESTING.vested_amount(1_000, 150), 500);
+        assert_eq!(VESTING.vested_amount(1_000, 175), 750);
+        // large totals don't overflow the intermediate product
+        assert_eq!(VESTING.vested_amount(u64::MAX, 150), u64::MAX / 2);
+    }
+
+    #[test]
+    fn vests_everything_from_the_end() {
+        assert_eq!(VESTING.vested_amount(1_000, 200), 1_000);
+        assert_eq!(VESTING.vested_amount(1_000, i64::MAX), 1_000);
+    }
+
+    #[test]
+    fn releases_the_vested_portion_over_several_withdrawals() {
+        let mut expense_package = package(1_000);
+        expense_package.set_approved_amount(Some(800)).unwrap();
+        expense_package.vesting = Some(VESTING);
+
+        assert_eq!(
+            expense_package.release(149),
+            Err(SlideError::NothingVested.into())
+        );
+        assert_eq!(expense_package.release(150), Ok(400));
+        assert_eq!(
+            expense_package.release(150),
+            Err(SlideError::NothingVested.into())
+        );
+        assert_eq!(expense_package.release(175), Ok(200));
+        assert_eq!(expense_package.state, ExpensePackageState::Approved);
+        assert_eq!(expense_package.release(250), Ok(200));
+        assert_eq!(expense_package.withdrawn_amount, 800);
+        assert_eq!(expense_package.state, ExpensePackageState::Paid);
+    }
+
+    #[test]
+    fn releases_everything_without_a_vesting_schedule() {
+        let mut expense_package = package(1_000);
+        assert_eq!(expense_package.release(0), Ok(1_000));
+        assert_eq!(expense_package.state, ExpensePackageState::Paid);
+        assert_eq!(
+            expense_package.release(0),
+            Err(SlideError::NothingVested.into())
+        );
+    }
+
+    #[test]
+    fn rejects_execution_windows_below_the_minimum() {
+        let mut expense_manager = ExpenseManager::default();
+        assert_eq!(
+            expense_manager.set_proposal_execution_window(Some(0)),
+            Err(SlideError::ExecutionWindowTooShort.into())
+        );
+        assert_eq!(expense_manager.proposal_execution_window, None);
+        expense_manager
+            .set_proposal_execution_window(Some(ExpenseManager::MIN_PROPOSAL_EXECUTION_WINDOW))
+            .unwrap();
+        expense_manager.set_proposal_execution_window(None).unwrap();
+        assert_eq!(expense_manager.proposal_execution_window, None);
+    }
+
+    #[test]
+    fn raises_the_withdrawal_timelock_straight_away() {
+        let mut expense_manager = ExpenseManager::default();
+        expense_manager
+            .set_withdrawal_timelock(Some(3_600), 100)
+            .unwrap();
+        assert_eq!(expense_manager.withdrawal_timelock, Some(3_600));
+        assert_eq!(expense_manager.pending_withdrawal_timelock, None);
+    }
+
+    #[test]
+    fn queues_a_lower_withdrawal_timelock() {
+        let mut expense_manager = ExpenseManager {
+            withdrawal_timelock: Some(3_600),
+            ..ExpenseManager::default()
+        };
+        expense_manager.set_withdrawal_timelock(None, 100).unwrap();
+        assert_eq!(expense_manager.withdrawal_timelock, Some(3_600));
+        assert_eq!(expense_manager.pending_withdrawal_timelock, Some(0));
+        assert_eq!(expense_manager.pending_timelock_effective_at, 3_700);
+    }
+
+    #[test]
+    fn rejects_withdrawal_timelocks_above_the_maximum() {
+        let mut expense_manager = ExpenseManager::default();
+        assert_eq!(
+            expense_manager
+                .set_withdrawal_timelock(Some(ExpenseManager::MAX_WITHDRAWAL_TIMELOCK + 1), 100),
+            Err(SlideError::WithdrawalTimelockTooLong.into())
+        );
+        assert_eq!(expense_manager.withdrawal_timelock, None);
+    }
+
+    fn manager_address(seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &crate::ID).0
+    }
+
+    #[test]
+    fn derives_managers_from_their_namespace() {
+        let creator = Pubkey::new_unique();
+        let expense_manager = ExpenseManager {
+            name: String::from("payroll"),
+            namespace: Some(creator),
+            ..ExpenseManager::default()
+        };
+        let address = manager_address(&[
+            b"expense-manager",
+            expense_manager.namespace_seed(),
+            expense_manager.name.as_bytes(),
+        ]);
+
+        assert_eq!(
+            address,
+            manager_address(&[b"expense-manager", creator.as_ref(), b"payroll"])
+        );
+        assert_ne!(address, manager_address(&[b"expense-manager", b"payroll"]));
+    }
+
+    #[test]
+    fn derives_legacy_managers_from_their_name_only() {
+        let expense_manager = ExpenseManager {
+            name: String::from("payroll"),
+            namespace: None,
+            ..ExpenseManager::default()
+        };
+
+        assert_eq!(
+            manager_address(&[
+                b"expense-manager",
+                expense_manager.namespace_seed(),
+                expense_manager.name.as_bytes(),
+            ]),
+            manager_address(&[b"expense-manager", b"payroll"])
+        );
+    }
+}
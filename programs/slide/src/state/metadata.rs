@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// Display metadata for an ExpenseManager, kept apart from the manager so that it
+/// can change without affecting the name used in the manager's PDA seeds
+#[account]
+#[derive(Default, Eq, PartialEq)]
+pub struct ExpenseManagerMetadata {
+    pub bump: u8,
+    pub expense_manager: Pubkey,
+    pub display_name: String,
+    pub description: String,
+    pub logo_uri: String,
+    pub contact: String,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl ExpenseManagerMetadata {
+    pub const MAX_DISPLAY_NAME_LEN: usize = 64;
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+    pub const MAX_LOGO_URI_LEN: usize = 128;
+    pub const MAX_CONTACT_LEN: usize = 64;
+    pub const VERSION: u8 = 1;
+
+    // bump: 1
+    // expense_manager: 32
+    // display_name: 4 + 64
+    // description: 4 + 256
+    // logo_uri: 4 + 128
+    // contact: 4 + 64
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize = 1 + 32 + 4 + 64 + 4 + 256 + 4 + 128 + 4 + 64 + 1 + 32;
+
+    pub fn validate(&self) -> bool {
+        self.display_name.len() <= Self::MAX_DISPLAY_NAME_LEN
+            && self.description.len() <= Self::MAX_DESCRIPTION_LEN
+            && self.logo_uri.len() <= Self::MAX_LOGO_URI_LEN
+            && self.contact.len() <= Self::MAX_CONTACT_LEN
+    }
+}
@@ -1,13 +1,23 @@
 pub mod access;
+pub mod audit;
 pub mod expense;
+pub mod metadata;
 pub mod proposal;
+pub mod recurring;
 pub mod spl_gov;
 pub mod squads;
+pub mod stats;
 pub mod user;
+pub mod withdrawal;
 
 pub use access::*;
+pub use audit::*;
 pub use expense::*;
+pub use metadata::*;
 pub use proposal::*;
+pub use recurring::*;
 pub use spl_gov::*;
 pub use squads::*;
+pub use stats::*;
 pub use user::*;
+pub use withdrawal::*;
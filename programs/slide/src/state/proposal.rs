@@ -1,13 +1,58 @@
+use crate::utils::{VersionedAccount, VERSION_FIELDS_SIZE};
 use anchor_lang::prelude::*;
 
 #[account]
 pub struct ProposalExecution {
     pub proposal: Pubkey,
     pub executed_at: i64, // UnixTimestamp
+    pub version: u8,
+    pub reserved: [u8; 32],
+    // receives the rent back when the record is closed, None for records which predate it
+    pub executed_by: Option<Pubkey>,
 }
 
 impl ProposalExecution {
+    pub const VERSION: u8 = 2;
+    // how long a record must be kept while its proposal still exists
+    pub const RETENTION_PERIOD: i64 = 30 * 24 * 60 * 60;
+
     // proposal: 32
     // executed_at: 8
-    pub const MAX_SIZE: usize = 32 + 8;
+    // version: 1
+    // reserved: 32
+    // executed_by: 33
+    pub const MAX_SIZE: usize = 32 + 8 + 1 + 32 + 33;
+    // executed_by
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] = [0, VERSION_FIELDS_SIZE, 33];
+}
+
+impl VersionedAccount for ProposalExecution {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
+}
+
+/// Outlives a closed Squads manager so that a manager re-created at the same address and
+/// bound to the same squad can't execute proposals below the old manager's watermark
+#[account]
+#[derive(Default)]
+pub struct ProposalWatermark {
+    pub bump: u8,
+    pub min_proposal_index: u32,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl ProposalWatermark {
+    pub const VERSION: u8 = 1;
+
+    // bump: 1
+    // min_proposal_index: 4
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize = 1 + 4 + 1 + 32;
 }
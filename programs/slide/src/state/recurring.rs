@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+/// Template for an expense which recurs every `period` seconds, such as a stipend.
+/// Once approved, each payout can be cranked by anyone when it falls due, until
+/// the end date or cap is reached. Every template has at least one of them.
+#[account]
+#[derive(Default, Eq, PartialEq)]
+pub struct RecurringExpense {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub expense_manager: Pubkey,
+    pub payee: Pubkey,
+    pub name: String,
+    pub amount: u64,
+    pub period: u32,         // seconds
+    pub next_payout_at: i64, // UnixTimestamp
+    pub end: Option<i64>,    // UnixTimestamp
+    pub cap: Option<u64>,
+    pub total_paid: u64,
+    pub category: Option<u8>,
+    pub nonce: u32,
+    pub approved_by: Option<Pubkey>, // None until a reviewer or governance approves
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl RecurringExpense {
+    pub const MAX_NAME_LEN: usize = 64;
+    pub const VERSION: u8 = 1;
+
+    // bump: 1
+    // owner: 32
+    // expense_manager: 32
+    // payee: 32
+    // name: 4 + 64
+    // amount: 8
+    // period: 4
+    // next_payout_at: 8
+    // end: 9
+    // cap: 9
+    // total_paid: 8
+    // category: 2
+    // nonce: 4
+    // approved_by: 33
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize =
+        1 + 32 + 32 + 32 + 4 + 64 + 8 + 4 + 8 + 9 + 9 + 8 + 2 + 4 + 33 + 1 + 32;
+
+    pub fn validate(&self) -> bool {
+        self.name.len() <= Self::MAX_NAME_LEN
+    }
+
+    /// Whether another payout would run past the end date or cap
+    pub fn is_complete(&self) -> bool {
+        let past_end = self.end.map_or(false, |end| self.next_payout_at > end);
+        let over_cap = self.cap.map_or(false, |cap| {
+            self.total_paid
+                .checked_add(self.amount)
+                .map_or(true, |total| total > cap)
+        });
+        past_end || over_cap
+    }
+
+    /// Records the approval, skipping periods that fell due before it so a
+    /// backdated start can't be paid out in a burst of catch-up payouts
+    pub fn approve(&mut self, approved_by: Pubkey, now: i64) {
+        self.approved_by = Some(approved_by);
+        self.next_payout_at = self.next_payout_at.max(now);
+    }
+}
@@ -17,7 +17,8 @@ pub struct TokenOwnerRecord(TokenOwnerRecordV2);
 
 impl anchor_lang::AccountDeserialize for TokenOwnerRecord {
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
-        Ok(BorshDeserialize::deserialize(buf).unwrap())
+        BorshDeserialize::deserialize(buf)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
     }
 }
 
@@ -36,7 +37,8 @@ pub struct Governance(GovernanceV2);
 
 impl anchor_lang::AccountDeserialize for Governance {
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
-        Ok(BorshDeserialize::deserialize(buf).unwrap())
+        BorshDeserialize::deserialize(buf)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
     }
 }
 
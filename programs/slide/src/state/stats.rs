@@ -0,0 +1,157 @@
+use crate::state::ExpensePackageState;
+use crate::utils::{SlideError, VersionedAccount, VERSION_FIELDS_SIZE};
+use anchor_lang::prelude::*;
+
+/// Running totals for an ExpenseManager, kept up to date by every package and
+/// withdrawal instruction so that dashboards don't need to scan ExpensePackages.
+/// Managers which had packages before their stats account was created only count
+/// activity from that point on, so per-state counts saturate at zero.
+#[account]
+#[derive(Default, Eq, PartialEq)]
+pub struct ExpenseManagerStats {
+    pub bump: u8,
+    pub expense_manager: Pubkey,
+    // lamport totals
+    pub total_submitted: u64,
+    pub total_approved: u64,
+    pub total_denied: u64,
+    pub total_paid: u64,
+    pub total_withdrawn: u64,
+    // number of packages currently in each state
+    pub created_packages: u64,
+    pub pending_packages: u64,
+    pub denied_packages: u64,
+    pub approved_packages: u64,
+    pub auto_approved_packages: u64,
+    pub paid_packages: u64,
+    pub expired_packages: u64,
+    pub reconciling_packages: u64,
+    pub reconciled_packages: u64,
+    // false when the stats account was added to a manager which may already have had
+    // packages, in which case the counts above can't be trusted to settle the manager
+    pub counts_all_packages: bool,
+    // advances approved but not yet reconciled, including ones already paid out
+    pub unreconciled_advances: u64,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl ExpenseManagerStats {
+    pub const VERSION: u8 = 1;
+
+    // bump: 1
+    // expense_manager: 32
+    // totals: 5 * 8
+    // package counts: 9 * 8
+    // counts_all_packages: 1
+    // unreconciled_advances: 8
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize =
+        Self::UNVERSIONED_SIZE + Self::APPENDED_BEFORE_VERSIONING + VERSION_FIELDS_SIZE;
+    // bump through the first six package counts, written before layouts were versioned
+    const UNVERSIONED_SIZE: usize = 1 + 32 + 5 * 8 + 6 * 8;
+    // expired, reconciling and reconciled counts, counts_all_packages and
+    // unreconciled_advances were appended to unversioned stats, which are all migrated
+    // as the oldest layout. Stats which already had some of them keep a few unused
+    // bytes at the end.
+    const APPENDED_BEFORE_VERSIONING: usize = 3 * 8 + 1 + 8;
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] =
+        [0, Self::APPENDED_BEFORE_VERSIONING + VERSION_FIELDS_SIZE];
+
+    fn package_count(&mut self, state: &ExpensePackageState) -> &mut u64 {
+        match state {
+            ExpensePackageState::Created => &mut self.created_packages,
+            ExpensePackageState::Pending => &mut self.pending_packages,
+            ExpensePackageState::Denied => &mut self.denied_packages,
+            ExpensePackageState::Approved => &mut self.approved_packages,
+            ExpensePackageState::AutoApproved => &mut self.auto_approved_packages,
+            ExpensePackageState::Paid => &mut self.paid_packages,
+            ExpensePackageState::Expired => &mut self.expired_packages,
+            ExpensePackageState::Reconciling => &mut self.reconciling_packages,
+            ExpensePackageState::Reconciled => &mut self.reconciled_packages,
+        }
+    }
+
+    /// Moves a package between state counts, `from` is None for newly created packages
+    pub fn move_package(
+        &mut self,
+        from: Option<&ExpensePackageState>,
+        to: &ExpensePackageState,
+    ) -> Result<()> {
+        if let Some(from) = from {
+            let count = self.package_count(from);
+            *count = count.saturating_sub(1);
+        }
+        let count = self.package_count(to);
+        *count = count.checked_add(1).ok_or(SlideError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Drafts and submitted packages which have not been denied or paid yet, or are
+    /// awaiting reconciliation. Approved advances are counted again until they are
+    /// reconciled.
+    pub fn outstanding_packages(&self) -> u64 {
+        self.created_packages
+            .saturating_add(self.pending_packages)
+            .saturating_add(self.approved_packages)
+            .saturating_add(self.auto_approved_packages)
+            .saturating_add(self.reconciling_packages)
+            .saturating_add(self.unreconciled_advances)
+    }
+
+    fn add(total: &mut u64, amount: u64) -> Result<()> {
+        *total = total
+            .checked_add(amount)
+            .ok_or(SlideError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_submission(&mut self, quantity: u64) -> Result<()> {
+        Self::add(&mut self.total_submitted, quantity)
+    }
+
+    pub fn record_approval(&mut self, quantity: u64) -> Result<()> {
+        Self::add(&mut self.total_approved, quantity)
+    }
+
+    /// Takes back approved funds which returned to the manager without being paid
+    pub fn reverse_approval(&mut self, amount: u64) {
+        self.total_approved = self.total_approved.saturating_sub(amount);
+    }
+
+    pub fn record_denial(&mut self, quantity: u64) -> Result<()> {
+        Self::add(&mut self.total_denied, quantity)
+    }
+
+    pub fn record_payment(&mut self, amount: u64) -> Result<()> {
+        Self::add(&mut self.total_paid, amount)
+    }
+
+    /// Takes back paid funds which were refunded to the manager
+    pub fn reverse_payment(&mut self, amount: u64) {
+        self.total_paid = self.total_paid.saturating_sub(amount);
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        Self::add(&mut self.total_withdrawn, amount)
+    }
+
+    pub fn open_advance(&mut self) -> Result<()> {
+        Self::add(&mut self.unreconciled_advances, 1)
+    }
+
+    pub fn close_advance(&mut self) {
+        self.unreconciled_advances = self.unreconciled_advances.saturating_sub(1);
+    }
+}
+
+impl VersionedAccount for ExpenseManagerStats {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::{VersionedAccount, VERSION_FIELDS_SIZE};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -7,12 +8,46 @@ pub struct UserData {
     pub user: Pubkey,
     pub username: String,
     pub real_name: String,
+    // next nonce for the user's packages, shared by every manager since package seeds
+    // already include the manager
+    pub expense_package_nonce: u32,
+    pub version: u8,
+    pub reserved: [u8; 32],
 }
 
 impl UserData {
+    pub const MAX_USERNAME_LEN: usize = 64;
+    pub const MAX_REAL_NAME_LEN: usize = 128;
+    pub const VERSION: u8 = 1;
+
     // bump: 1
     // user: 32
-    // username: 64
-    // real_name: 128
-    pub const MAX_SIZE: usize = 1 + 32 + 64 + 128;
+    // username: 4 + 64
+    // real_name: 4 + 128
+    // expense_package_nonce: 4
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize = 1 + 32 + 4 + 64 + 4 + 128 + 4 + 1 + 32;
+    const APPENDED_BY_VERSION: [usize; Self::VERSION as usize + 1] = [0, VERSION_FIELDS_SIZE];
+
+    pub fn validate(&self) -> bool {
+        self.username.len() <= Self::MAX_USERNAME_LEN
+            && self.real_name.len() <= Self::MAX_REAL_NAME_LEN
+    }
+
+    /// Whether initialize_user has filled in the profile, as opposed to the account only
+    /// having been created alongside the user's first expense package
+    pub fn has_profile(&self) -> bool {
+        !self.username.is_empty() || !self.real_name.is_empty()
+    }
+}
+
+impl VersionedAccount for UserData {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn appended_by_version() -> &'static [usize] {
+        &Self::APPENDED_BY_VERSION
+    }
 }
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// A withdrawal from a timelocked ExpenseManager which governance has approved,
+/// waiting out the manager's delay before it can be executed
+#[account]
+#[derive(Default, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    pub bump: u8,
+    pub expense_manager: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub requested_at: i64,  // UnixTimestamp
+    pub executable_at: i64, // UnixTimestamp
+    // paid the rent for this request and receives it back once it is closed
+    pub payer: Pubkey,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl WithdrawalRequest {
+    pub const VERSION: u8 = 1;
+
+    // bump: 1
+    // expense_manager: 32
+    // destination: 32
+    // amount: 8
+    // requested_at: 8
+    // executable_at: 8
+    // payer: 32
+    // version: 1
+    // reserved: 32
+    pub const MAX_SIZE: usize = 1 + 32 + 32 + 8 + 8 + 8 + 32 + 1 + 32;
+}
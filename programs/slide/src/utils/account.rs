@@ -0,0 +1,105 @@
+use crate::utils::{transfer_lamports, SlideError};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Reallocs a program-owned account to `new_size` bytes, keeping it rent-exempt.
+/// Growing charges the rent difference to `payer`, shrinking refunds it.
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    new_size: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let current_size = account.data_len();
+    if new_size == current_size {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let current_minimum_balance = rent.minimum_balance(current_size);
+    let new_minimum_balance = rent.minimum_balance(new_size);
+
+    if new_minimum_balance > current_minimum_balance {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            new_minimum_balance - current_minimum_balance,
+        )?;
+    } else if current_minimum_balance > new_minimum_balance {
+        transfer_lamports(
+            account,
+            payer,
+            current_minimum_balance - new_minimum_balance,
+        )?;
+    }
+
+    account.realloc(new_size, false)?;
+
+    Ok(())
+}
+
+/// Size of the `version` and `reserved` fields appended to every account layout
+pub const VERSION_FIELDS_SIZE: usize = 1 + 32;
+
+/// Accounts whose layout only grows by appending fields, tagged with the version
+/// of the layout they were last written with
+pub trait VersionedAccount {
+    fn version(&self) -> u8;
+
+    /// Bytes appended to the layout by each version, index 0 being accounts created
+    /// before layouts were versioned
+    fn appended_by_version() -> &'static [usize];
+
+    /// Bytes appended to the layout since `version`, i.e. MAX_SIZE less the stored layout
+    fn appended_since(version: u8) -> Result<usize> {
+        let appended = Self::appended_by_version();
+        require!(
+            (version as usize) < appended.len(),
+            SlideError::UnknownAccountVersion
+        );
+        Ok(appended[version as usize + 1..].iter().sum())
+    }
+}
+
+/// Loads an account written with an older layout, growing it by the fields appended
+/// since its stored version and then to `space` for the current layout.
+/// Accounts are never shrunk.
+pub fn migrate_account<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: impl Fn(&T) -> usize,
+) -> Result<Account<'info, T>>
+where
+    T: VersionedAccount + AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    // every appended field comes after `version`, so padding the data out to the
+    // current layout reads the stored version of any older layout (0 if unversioned)
+    let stored_version = {
+        let mut data = account.try_borrow_data()?.to_vec();
+        data.resize(data.len() + T::appended_since(0)?, 0);
+        T::try_deserialize(&mut &data[..])?.version()
+    };
+    let appended_size = T::appended_since(stored_version)?;
+    if appended_size > 0 {
+        resize_account(
+            account,
+            account.data_len() + appended_size,
+            payer,
+            system_program,
+        )?;
+    }
+
+    let migrated: Account<'info, T> = Account::try_from(account)?;
+    let required_space = space(&migrated);
+    if required_space > account.data_len() {
+        resize_account(account, required_space, payer, system_program)?;
+    }
+
+    Ok(migrated)
+}
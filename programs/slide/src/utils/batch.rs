@@ -0,0 +1,330 @@
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{pay_from_manager, transfer_lamports, SlideError};
+use anchor_lang::prelude::*;
+
+/// Loads an ExpensePackage passed through remaining_accounts, applying the same
+/// seeds and manager checks as the single-package account constraints
+pub(crate) fn load_expense_package<'info>(
+    info: &AccountInfo<'info>,
+    expense_manager: &Pubkey,
+    nonce: u32,
+    program_id: &Pubkey,
+) -> Result<Account<'info, ExpensePackage>> {
+    let expense_package: Account<'info, ExpensePackage> = Account::try_from(info)?;
+    let expected_address = Pubkey::create_program_address(
+        &[
+            b"expense-package",
+            expense_package.expense_manager.as_ref(),
+            expense_package.owner.as_ref(),
+            &nonce.to_le_bytes(),
+            &[expense_package.bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| SlideError::InvalidPackageAccount)?;
+    require!(
+        expected_address == info.key(),
+        SlideError::InvalidPackageAccount
+    );
+    require!(
+        expense_package.expense_manager == *expense_manager,
+        SlideError::PackageOwnershipMismatch
+    );
+    require!(
+        expense_package.version == ExpensePackage::VERSION,
+        SlideError::AccountNotMigrated
+    );
+    Ok(expense_package)
+}
+
+fn load_pending_expense_package<'info>(
+    info: &AccountInfo<'info>,
+    expense_manager: &ExpenseManager,
+    expense_manager_key: &Pubkey,
+    authority: &Pubkey,
+    nonce: u32,
+    program_id: &Pubkey,
+) -> Result<Account<'info, ExpensePackage>> {
+    let expense_package = load_expense_package(info, expense_manager_key, nonce, program_id)?;
+    require!(
+        expense_package.state == ExpensePackageState::Pending,
+        SlideError::PackageNotPending
+    );
+    require!(
+        expense_package.owner != *authority,
+        SlideError::UserCannotApproveOrDenyOwnExpense
+    );
+    expense_manager.check_category_reviewer(expense_package.category, authority)?;
+    Ok(expense_package)
+}
+
+pub(crate) fn check_unique(seen: &mut Vec<Pubkey>, key: Pubkey) -> Result<()> {
+    require!(!seen.contains(&key), SlideError::DuplicatePackageInBatch);
+    seen.push(key);
+    Ok(())
+}
+
+/// Approves a single package, shared by the single and batch approve instructions of both
+/// governance integrations. The package must already have passed the pending and
+/// ownership checks, and `payee` must match its payout address.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_expense_package<'info>(
+    expense_package: &mut Account<'info, ExpensePackage>,
+    expense_manager: &mut Account<'info, ExpenseManager>,
+    audit_log: &mut AuditLog,
+    manager_stats: &mut ExpenseManagerStats,
+    authority: &Pubkey,
+    payee: &AccountInfo<'info>,
+    approved_amount: Option<u64>,
+    vesting: Option<VestingSchedule>,
+) -> Result<()> {
+    expense_manager.check_category_reviewer(expense_package.category, authority)?;
+    expense_package.set_approved_amount(approved_amount)?;
+    let approved_amount = expense_package.approved_quantity();
+    if let Some(vesting) = &vesting {
+        require!(vesting.validate(), SlideError::InvalidVestingSchedule);
+    }
+    expense_manager.record_category_spend(expense_package.category, approved_amount)?;
+
+    // with direct payout, funds skip the package account and go straight to the payee,
+    // unless they have to be held there while vesting
+    let direct_payout = expense_manager.direct_payout && vesting.is_none();
+    let recipient_info = if direct_payout {
+        expense_package.state = ExpensePackageState::Paid;
+        expense_package.withdrawn_amount = approved_amount;
+        payee.clone()
+    } else {
+        expense_package.state = ExpensePackageState::Approved;
+        expense_package.to_account_info()
+    };
+    pay_from_manager(
+        &expense_manager.to_account_info(),
+        &recipient_info,
+        approved_amount,
+    )?;
+
+    manager_stats.move_package(Some(&ExpensePackageState::Pending), &expense_package.state)?;
+    manager_stats.record_approval(approved_amount)?;
+    if direct_payout {
+        manager_stats.record_payment(approved_amount)?;
+    }
+    if expense_package.advance {
+        manager_stats.open_advance()?;
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    expense_package.approved_at = timestamp;
+    expense_package.vesting = vesting.clone();
+    expense_package.withdrawal_deadline = expense_manager.withdrawal_deadline;
+    audit_log.append(
+        *authority,
+        AuditAction::PackageApproved,
+        expense_package.key(),
+        approved_amount,
+        timestamp,
+    );
+    emit!(ExpensePackageApproved {
+        expense_manager: expense_manager.key(),
+        expense_package: expense_package.key(),
+        reviewer: *authority,
+        quantity: expense_package.quantity,
+        approved_amount,
+        vesting,
+        timestamp,
+    });
+    if direct_payout {
+        audit_log.append(
+            *authority,
+            AuditAction::PackagePaid,
+            expense_package.key(),
+            approved_amount,
+            timestamp,
+        );
+        emit!(ExpensePackagePaid {
+            expense_manager: expense_manager.key(),
+            expense_package: expense_package.key(),
+            payee: payee.key(),
+            authority: *authority,
+            amount: approved_amount,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+/// Approves every package in the batch. `remaining_accounts` holds a
+/// (package, payee) pair for each nonce, in order, and `approved_amounts`
+/// holds the amount approved for each, None approving the whole quantity.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_expense_package_batch<'info>(
+    expense_manager: &mut Account<'info, ExpenseManager>,
+    audit_log: &mut AuditLog,
+    manager_stats: &mut ExpenseManagerStats,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    nonces: &[u32],
+    approved_amounts: &[Option<u64>],
+    program_id: &Pubkey,
+) -> Result<()> {
+    require!(
+        remaining_accounts.len() == nonces.len() * 2 && approved_amounts.len() == nonces.len(),
+        SlideError::BatchAccountsMismatch
+    );
+    require!(!expense_manager.paused, SlideError::ExpenseManagerPaused);
+
+    let expense_manager_key = expense_manager.key();
+    let mut seen = Vec::with_capacity(nonces.len());
+    for ((nonce, approved_amount), accounts) in nonces
+        .iter()
+        .zip(approved_amounts.iter())
+        .zip(remaining_accounts.chunks(2))
+    {
+        let (package_info, payee) = (&accounts[0], &accounts[1]);
+        check_unique(&mut seen, package_info.key())?;
+        let mut expense_package = load_pending_expense_package(
+            package_info,
+            expense_manager,
+            &expense_manager_key,
+            authority,
+            *nonce,
+            program_id,
+        )?;
+        require!(
+            payee.key() == expense_package.payout_address(),
+            SlideError::PayeeMismatch
+        );
+        approve_expense_package(
+            &mut expense_package,
+            expense_manager,
+            audit_log,
+            manager_stats,
+            authority,
+            payee,
+            *approved_amount,
+            None,
+        )?;
+        expense_package.exit(program_id)?;
+    }
+
+    Ok(())
+}
+
+/// Denies every package in the batch. `remaining_accounts` holds one package per nonce, in order.
+pub fn deny_expense_package_batch<'info>(
+    expense_manager: &Account<'info, ExpenseManager>,
+    audit_log: &mut AuditLog,
+    manager_stats: &mut ExpenseManagerStats,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    nonces: &[u32],
+    program_id: &Pubkey,
+) -> Result<()> {
+    require!(
+        remaining_accounts.len() == nonces.len(),
+        SlideError::BatchAccountsMismatch
+    );
+
+    let expense_manager_key = expense_manager.key();
+    let timestamp = Clock::get()?.unix_timestamp;
+    let mut seen = Vec::with_capacity(nonces.len());
+    for (nonce, package_info) in nonces.iter().zip(remaining_accounts.iter()) {
+        check_unique(&mut seen, package_info.key())?;
+        let mut expense_package = load_pending_expense_package(
+            package_info,
+            expense_manager,
+            &expense_manager_key,
+            authority,
+            *nonce,
+            program_id,
+        )?;
+        expense_package.state = ExpensePackageState::Denied;
+        expense_package.exit(program_id)?;
+        manager_stats.move_package(
+            Some(&ExpensePackageState::Pending),
+            &ExpensePackageState::Denied,
+        )?;
+        manager_stats.record_denial(expense_package.quantity)?;
+
+        audit_log.append(
+            *authority,
+            AuditAction::PackageDenied,
+            expense_package.key(),
+            0,
+            timestamp,
+        );
+        emit!(ExpensePackageDenied {
+            expense_manager: expense_manager_key,
+            expense_package: expense_package.key(),
+            reviewer: *authority,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+/// Pays out every approved package in the batch. `remaining_accounts` holds a
+/// (package, payee) pair for each nonce, in order.
+pub fn pay_expense_package_batch<'info>(
+    expense_manager: &Account<'info, ExpenseManager>,
+    audit_log: &mut AuditLog,
+    manager_stats: &mut ExpenseManagerStats,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    nonces: &[u32],
+    program_id: &Pubkey,
+) -> Result<()> {
+    require!(
+        remaining_accounts.len() == nonces.len() * 2,
+        SlideError::BatchAccountsMismatch
+    );
+    require!(!expense_manager.paused, SlideError::ExpenseManagerPaused);
+
+    let expense_manager_key = expense_manager.key();
+    let timestamp = Clock::get()?.unix_timestamp;
+    let mut seen = Vec::with_capacity(nonces.len());
+    for (nonce, accounts) in nonces.iter().zip(remaining_accounts.chunks(2)) {
+        let (package_info, payee) = (&accounts[0], &accounts[1]);
+        check_unique(&mut seen, package_info.key())?;
+        let mut expense_package =
+            load_expense_package(package_info, &expense_manager_key, *nonce, program_id)?;
+        require!(
+            expense_package.state == ExpensePackageState::Approved,
+            SlideError::PackageNotApproved
+        );
+        require!(
+            payee.key() == expense_package.payout_address(),
+            SlideError::PayeeMismatch
+        );
+        let amount = expense_package.release(timestamp)?;
+        transfer_lamports(package_info, payee, amount)?;
+        expense_package.exit(program_id)?;
+        if expense_package.state == ExpensePackageState::Paid {
+            manager_stats.move_package(
+                Some(&ExpensePackageState::Approved),
+                &ExpensePackageState::Paid,
+            )?;
+        }
+        manager_stats.record_payment(amount)?;
+
+        audit_log.append(
+            *authority,
+            AuditAction::PackagePaid,
+            expense_package.key(),
+            amount,
+            timestamp,
+        );
+        emit!(ExpensePackagePaid {
+            expense_manager: expense_manager_key,
+            expense_package: expense_package.key(),
+            payee: payee.key(),
+            authority: *authority,
+            amount,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use crate::events::*;
+use crate::state::*;
+use crate::utils::batch::{check_unique, load_expense_package};
+use crate::utils::SlideError;
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+/// Settles everything attached to an ExpenseManager ahead of closing it.
+/// `remaining_accounts` holds one package per nonce to force-deny, then the manager's
+/// AccessRecords to close, then a (source, destination) pair for each manager-owned
+/// token account to sweep into the treasury. The metadata account is closed too if
+/// the manager has one.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_expense_manager<'info>(
+    expense_manager: &Account<'info, ExpenseManager>,
+    audit_log: &mut AuditLog,
+    manager_stats: &mut ExpenseManagerStats,
+    manager_metadata: &AccountInfo<'info>,
+    authority: &Pubkey,
+    treasury: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    remaining_accounts: &[AccountInfo<'info>],
+    force_deny_nonces: &[u32],
+    program_id: &Pubkey,
+) -> Result<()> {
+    // packages from before the stats account existed would be missed by the counts
+    require!(
+        manager_stats.counts_all_packages,
+        SlideError::ManagerStatsIncomplete
+    );
+    // queued withdrawals would otherwise be swept into the treasury early
+    require!(
+        expense_manager.withdrawal_request_count == 0,
+        SlideError::OutstandingWithdrawalRequests
+    );
+    require!(
+        remaining_accounts.len() >= force_deny_nonces.len(),
+        SlideError::BatchAccountsMismatch
+    );
+    let (package_infos, rest) = remaining_accounts.split_at(force_deny_nonces.len());
+    // records are told apart from token accounts by their owner rather than by
+    // access_record_count, which misses records granted before managers counted them
+    let records_end = rest
+        .iter()
+        .position(|info| info.owner != program_id)
+        .unwrap_or(rest.len());
+    let (access_record_infos, token_account_infos) = rest.split_at(records_end);
+    require!(
+        token_account_infos.len() % 2 == 0,
+        SlideError::BatchAccountsMismatch
+    );
+
+    let expense_manager_key = expense_manager.key();
+    let timestamp = Clock::get()?.unix_timestamp;
+    let mut seen = Vec::with_capacity(force_deny_nonces.len());
+    for (nonce, package_info) in force_deny_nonces.iter().zip(package_infos.iter()) {
+        check_unique(&mut seen, package_info.key())?;
+        let mut expense_package =
+            load_expense_package(package_info, &expense_manager_key, *nonce, program_id)?;
+        // approved packages are owed to their owners, so only unreviewed ones are denied
+        let previous_state = expense_package.state.clone();
+        require!(
+            matches!(
+                previous_state,
+                ExpensePackageState::Created | ExpensePackageState::Pending
+            ),
+            SlideError::PackageNotPending
+        );
+        expense_package.state = ExpensePackageState::Denied;
+        expense_package.exit(program_id)?;
+        manager_stats.move_package(Some(&previous_state), &ExpensePackageState::Denied)?;
+        // drafts were never submitted, so their quantity isn't in the totals
+        if previous_state == ExpensePackageState::Pending {
+            manager_stats.record_denial(expense_package.quantity)?;
+        }
+
+        audit_log.append(
+            *authority,
+            AuditAction::PackageDenied,
+            expense_package.key(),
+            0,
+            timestamp,
+        );
+        emit!(ExpensePackageDenied {
+            expense_manager: expense_manager_key,
+            expense_package: expense_package.key(),
+            reviewer: *authority,
+            timestamp,
+        });
+    }
+    require!(
+        manager_stats.outstanding_packages() == 0,
+        SlideError::OutstandingExpensePackages
+    );
+
+    let mut seen = Vec::with_capacity(access_record_infos.len());
+    let mut unclosed_records = expense_manager.access_record_count;
+    for access_record_info in access_record_infos {
+        check_unique(&mut seen, access_record_info.key())?;
+        let access_record: Account<'info, AccessRecord> = Account::try_from(access_record_info)?;
+        require!(
+            access_record.expense_manager == expense_manager_key,
+            SlideError::AccessRecordMismatch
+        );
+        let user = access_record.user;
+        access_record.close(treasury.clone())?;
+        unclosed_records = unclosed_records.saturating_sub(1);
+        emit!(AccessRevoked {
+            expense_manager: expense_manager_key,
+            user,
+            timestamp,
+        });
+    }
+    // every counted record has to be closed, older records come on top of the count
+    require!(unclosed_records == 0, SlideError::OutstandingAccessRecords);
+
+    if manager_metadata.owner == program_id {
+        let metadata: Account<'info, ExpenseManagerMetadata> = Account::try_from(manager_metadata)?;
+        metadata.close(treasury.clone())?;
+    }
+
+    let manager_seeds = &[
+        b"expense-manager".as_ref(),
+        expense_manager.namespace_seed(),
+        expense_manager.name.as_bytes(),
+        &[expense_manager.bump],
+    ];
+    let signer_seeds = &[&manager_seeds[..]];
+    for accounts in token_account_infos.chunks(2) {
+        let (source_info, destination_info) = (&accounts[0], &accounts[1]);
+        let source: Account<'info, TokenAccount> = Account::try_from(source_info)?;
+        let destination: Account<'info, TokenAccount> = Account::try_from(destination_info)?;
+        require!(
+            source.owner == expense_manager_key
+                && destination.owner == treasury.key()
+                && destination.mint == source.mint,
+            SlideError::TokenAccountMismatch
+        );
+        if source.amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    Transfer {
+                        from: source_info.clone(),
+                        to: destination_info.clone(),
+                        authority: expense_manager.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                source.amount,
+            )?;
+        }
+        token::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: source_info.clone(),
+                destination: treasury.clone(),
+                authority: expense_manager.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    Ok(())
+}
@@ -34,5 +34,116 @@ pub enum SlideError {
     PackageNotApproved,
     #[msg("Insufficient funds exist in the manager to approve this expense")]
     ManagerInsufficientFunds,
+    #[msg("Provided data exceeds the maximum length for this field")]
     DataTooLarge,
+    #[msg("ExpensePackage cannot hold any more line items")]
+    TooManyLineItems,
+    #[msg("ExpensePackage has no line item at the given index")]
+    LineItemNotFound,
+    #[msg("ExpensePackage quantity is derived from its line items and cannot be set directly")]
+    QuantityDerivedFromLineItems,
+    #[msg("ExpensePackage cannot hold any more attachments")]
+    TooManyAttachments,
+    #[msg("ExpensePackage has no attachment at the given index")]
+    AttachmentNotFound,
+    #[msg("ExpenseManager requires at least one attachment before submission")]
+    PackageMissingAttachments,
+    #[msg("User has insufficient permissions to manage this ExpenseManager")]
+    UserCannotManageExpenseManager,
+    #[msg("ExpenseManager cannot hold any more categories")]
+    TooManyCategories,
+    #[msg("ExpenseManager has no category at the given index")]
+    CategoryNotFound,
+    #[msg("Approving this expense would exceed its category budget")]
+    CategoryBudgetExceeded,
+    #[msg("User is not an assigned reviewer for this expense category")]
+    UserNotCategoryReviewer,
+    #[msg("ExpensePackage has not been submitted or has already been reviewed")]
+    PackageNotPending,
+    #[msg("Payee does not match the ExpensePackage payee")]
+    PayeeMismatch,
+    #[msg("Remaining accounts do not match the nonces in the batch")]
+    BatchAccountsMismatch,
+    #[msg("ExpensePackage appears more than once in the batch")]
+    DuplicatePackageInBatch,
+    #[msg("ExpensePackage address does not match the given nonce")]
+    InvalidPackageAccount,
+    #[msg("Account has insufficient lamports for this transfer")]
+    InsufficientFunds,
+    #[msg("Bump seed was not found for account")]
+    BumpNotFound,
+    #[msg("ExpenseManager still has unsettled expense packages")]
+    OutstandingExpensePackages,
+    #[msg("AccessRecord does not belong to this ExpenseManager")]
+    AccessRecordMismatch,
+    #[msg("TokenAccount is not owned by the expected authority or has the wrong mint")]
+    TokenAccountMismatch,
+    #[msg("ExpenseManager is paused")]
+    ExpenseManagerPaused,
+    #[msg("Squad is under emergency lock")]
+    SquadEmergencyLock,
+    #[msg("Proposal voting has not started")]
+    ProposalNotStarted,
+    #[msg("Proposal is past the ExpenseManager execution window")]
+    ProposalExpired,
+    #[msg(
+        "ProposalExecution must be kept until its proposal is closed or the retention period ends"
+    )]
+    ProposalExecutionRetained,
+    #[msg("Rent receiver does not match the ProposalExecution payer")]
+    RentReceiverMismatch,
+    #[msg("ExpenseManager withdrawals are timelocked and must be queued")]
+    WithdrawalTimelocked,
+    #[msg("WithdrawalRequest is still within its timelock")]
+    WithdrawalRequestLocked,
+    #[msg("WithdrawalRequest timelock has passed and it can no longer be cancelled")]
+    WithdrawalRequestExecutable,
+    #[msg("ExpensePackage is not past any of the ExpenseManager deadlines")]
+    PackageNotOverdue,
+    #[msg("RecurringExpense period and amount must be greater than zero")]
+    InvalidRecurringSchedule,
+    #[msg("RecurringExpense has not been approved")]
+    RecurringExpenseNotApproved,
+    #[msg("RecurringExpense has already been approved")]
+    RecurringExpenseAlreadyApproved,
+    #[msg("RecurringExpense payout is not due yet")]
+    RecurringExpenseNotDue,
+    #[msg("RecurringExpense has reached its end date or cap")]
+    RecurringExpenseComplete,
+    #[msg("ExpensePackage is not a paid cash advance awaiting reconciliation")]
+    PackageNotAdvance,
+    #[msg("ExpensePackage has no reconciliation awaiting review")]
+    PackageNotReconciling,
+    #[msg("Approved amount must be non-zero and no larger than the package quantity")]
+    InvalidApprovedAmount,
+    #[msg("Vesting schedule must end after it starts, with the cliff in between")]
+    InvalidVestingSchedule,
+    #[msg("No vested funds are available to withdraw yet")]
+    NothingVested,
+    #[msg("Account was written with a layout version this program does not know")]
+    UnknownAccountVersion,
+    #[msg("Account must be migrated to the current layout first")]
+    AccountNotMigrated,
+    #[msg("Manager stats were added after packages existed, so they can't settle the manager")]
+    ManagerStatsIncomplete,
+    #[msg("A lower proposal could still be executed, so the watermark can't pass it")]
+    ProposalStillExecutable,
+    #[msg("No lower withdrawal timelock is waiting to apply")]
+    NoPendingTimelockChange,
+    #[msg("A lower withdrawal timelock only applies once the current one has passed")]
+    TimelockChangeNotReady,
+    #[msg("ExpenseManager has queued WithdrawalRequests which must be executed or cancelled")]
+    OutstandingWithdrawalRequests,
+    #[msg("UserData has already been initialized")]
+    UserAlreadyInitialized,
+    #[msg("ExpenseManager has AccessRecords which must be closed along with it")]
+    OutstandingAccessRecords,
+    #[msg("Proposal execution window is shorter than the minimum")]
+    ExecutionWindowTooShort,
+    #[msg("Withdrawal timelock is longer than the maximum")]
+    WithdrawalTimelockTooLong,
+    #[msg("Recurring expense needs an end date or a cap")]
+    RecurringExpenseUnbounded,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
 }
@@ -0,0 +1,35 @@
+use crate::utils::SlideError;
+use anchor_lang::prelude::*;
+
+/// Lamports held by `account` above its rent-exempt minimum
+pub fn available_lamports(account: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?;
+    let rent_exempt_lamports = rent.minimum_balance(account.data_len()).max(1);
+    Ok(account.lamports().saturating_sub(rent_exempt_lamports))
+}
+
+/// Moves lamports directly between two accounts, `from` must be owned by this program
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let mut from_balance = from.try_borrow_mut_lamports()?;
+    let mut to_balance = to.try_borrow_mut_lamports()?;
+    **from_balance = from_balance
+        .checked_sub(amount)
+        .ok_or(SlideError::InsufficientFunds)?;
+    **to_balance = to_balance
+        .checked_add(amount)
+        .ok_or(SlideError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Pays `amount` out of an ExpenseManager without dipping into its rent-exemption
+pub fn pay_from_manager(
+    expense_manager: &AccountInfo,
+    to: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        amount <= available_lamports(expense_manager)?,
+        SlideError::ManagerInsufficientFunds
+    );
+    transfer_lamports(expense_manager, to, amount)
+}
@@ -1,3 +1,13 @@
+pub mod account;
+pub mod batch;
+pub mod close;
 pub mod error;
+pub mod lamports;
+pub mod proposal;
 
+pub use account::*;
+pub use batch::*;
+pub use close::*;
 pub use error::*;
+pub use lamports::*;
+pub use proposal::*;
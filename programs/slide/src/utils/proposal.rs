@@ -0,0 +1,290 @@
+use crate::state::{ExpenseManager, Proposal, ProposalWatermark, Squad};
+use crate::utils::SlideError;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use std::str::FromStr;
+
+/// Checks that a Squads text proposal is titled as a Slide proposal and passed an
+/// Approve/Deny vote meeting the squad's quorum and support thresholds
+pub fn check_squads_proposal_passed(
+    proposal: &Proposal,
+    squad: &Squad,
+    squad_mint: &Mint,
+) -> Result<()> {
+    if proposal.votes_num != 2 {
+        return err!(SlideError::InvalidProposal);
+    }
+
+    let approve_label = proposal
+        .votes_labels
+        .get(0)
+        .ok_or(SlideError::InvalidProposal)?;
+    let deny_label = proposal
+        .votes_labels
+        .get(1)
+        .ok_or(SlideError::InvalidProposal)?;
+    if approve_label.trim_end() != "Approve" || deny_label.trim_end() != "Deny" {
+        return err!(SlideError::InvalidProposal);
+    }
+
+    let pass_votes = *proposal.votes.get(0).ok_or(SlideError::InvalidProposal)?;
+    let fail_votes = *proposal.votes.get(1).ok_or(SlideError::InvalidProposal)?;
+    if pass_votes < fail_votes {
+        return err!(SlideError::InvalidProposal);
+    }
+
+    // check quorum & support
+    let curr_quorum_percent;
+    let current_support_percent;
+    if proposal.execute_ready {
+        curr_quorum_percent =
+            (proposal.has_voted.len() as f32 / proposal.members_at_execute as f32) * 100.0;
+
+        current_support_percent = (pass_votes as f32 / proposal.supply_at_execute as f32) * 100.0;
+    } else {
+        curr_quorum_percent =
+            (proposal.has_voted.len() as f32 / squad.members.len() as f32) * 100.0;
+
+        current_support_percent = (pass_votes as f32 / squad_mint.supply as f32) * 100.0;
+    }
+
+    if curr_quorum_percent < squad.vote_quorum as f32 {
+        return err!(SlideError::InvalidProposal);
+    }
+
+    if current_support_percent < squad.vote_support as f32 {
+        return err!(SlideError::InvalidProposal);
+    }
+
+    if !proposal.title.starts_with("[SLIDE PROPOSAL]") {
+        return err!(SlideError::FailedToParseProposal);
+    }
+
+    Ok(())
+}
+
+/// Checks that a Squads proposal has opened for voting and, if the manager has an
+/// execution window, that it closed (or was executed in Squads) recently enough
+pub fn check_squads_proposal_timing(
+    proposal: &Proposal,
+    execution_window: Option<u32>,
+    now: i64,
+) -> Result<()> {
+    check_proposal_timing(
+        proposal.start_timestamp,
+        execution_window_start(proposal),
+        execution_window,
+        now,
+    )
+}
+
+/// A proposal's execution window counts from when it was executed in Squads, or else
+/// from when its vote closed
+fn execution_window_start(proposal: &Proposal) -> i64 {
+    if proposal.executed {
+        proposal.execution_date
+    } else {
+        proposal.close_timestamp
+    }
+}
+
+/// Checks that a proposal has started and, if there is an execution window, that
+/// `now` is no later than `window` seconds after `reference`
+pub(crate) fn check_proposal_timing(
+    start: i64,
+    reference: i64,
+    execution_window: Option<u32>,
+    now: i64,
+) -> Result<()> {
+    require!(now >= start, SlideError::ProposalNotStarted);
+    if let Some(window) = execution_window {
+        require!(
+            now <= reference.saturating_add(window as i64),
+            SlideError::ProposalExpired
+        );
+    }
+    Ok(())
+}
+
+/// Checks that a Squads manager's squad is not under emergency lock; managers governed
+/// some other way don't have a squad, so the account passed for them is ignored
+pub fn check_squad_unlocked(
+    expense_manager: &ExpenseManager,
+    squad_info: &AccountInfo,
+) -> Result<()> {
+    if let Some(squad_key) = expense_manager.squad {
+        require!(squad_info.key() == squad_key, SlideError::SquadMismatch);
+        require!(
+            *squad_info.owner == expense_manager.external_program_id,
+            SlideError::SquadMismatch
+        );
+        let squad = Squad::try_deserialize(&mut &squad_info.try_borrow_data()?[..])?;
+        require!(!squad.emergency_lock, SlideError::SquadEmergencyLock);
+    }
+    Ok(())
+}
+
+/// Checks that no proposal from the manager's watermark up to `proposal_index` can still
+/// be executed against it. `remaining_accounts` holds one account per index, in order:
+/// the proposal's execution record if Slide executed it, otherwise the proposal itself,
+/// which must be closed or past the manager's execution window
+pub fn check_lower_proposals_settled(
+    expense_manager: &Account<ExpenseManager>,
+    squad: &Pubkey,
+    proposal_index: u32,
+    remaining_accounts: &[AccountInfo],
+    now: i64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let lower_indices = expense_manager.min_proposal_index..proposal_index;
+    require!(
+        remaining_accounts.len() == lower_indices.len(),
+        SlideError::BatchAccountsMismatch
+    );
+
+    let expense_manager_key = expense_manager.key();
+    for (index, account) in lower_indices.zip(remaining_accounts.iter()) {
+        let (proposal, _) = Pubkey::find_program_address(
+            &[squad.as_ref(), &index.to_le_bytes(), b"!proposal"],
+            &expense_manager.external_program_id,
+        );
+        if account.key() == proposal {
+            if account.data_is_empty() || *account.owner != expense_manager.external_program_id {
+                continue;
+            }
+            let proposal_data = Proposal::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+            let expired = expense_manager
+                .proposal_execution_window
+                .map_or(false, |window| {
+                    now > execution_window_start(&proposal_data).saturating_add(window as i64)
+                });
+            require!(expired, SlideError::ProposalStillExecutable);
+        } else {
+            let (record, _) = Pubkey::find_program_address(
+                &[
+                    b"proposal-execution",
+                    expense_manager_key.as_ref(),
+                    proposal.as_ref(),
+                ],
+                program_id,
+            );
+            require!(
+                account.key() == record && account.owner == program_id && !account.data_is_empty(),
+                SlideError::ProposalStillExecutable
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reads the watermark left behind by a closed manager at the same address for the same
+/// squad, or 0 if there was none
+pub fn previous_proposal_watermark(
+    watermark_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<u32> {
+    if watermark_info.data_is_empty() || watermark_info.owner != program_id {
+        return Ok(0);
+    }
+    let watermark =
+        ProposalWatermark::try_deserialize(&mut &watermark_info.try_borrow_data()?[..])?;
+    Ok(watermark.min_proposal_index)
+}
+
+/// Parses the lamports to withdraw from a Squads withdrawal proposal, checking that it
+/// names this manager and treasury
+pub fn parse_withdrawal_proposal(
+    proposal: &Proposal,
+    expense_manager: &Pubkey,
+    treasury: &Pubkey,
+) -> Result<u64> {
+    // description
+    // first line:
+    //   starts with "lamports: "
+    //   rest of line should parse to a number of lamports <= (manager - rent exempt)
+    // second line:
+    //   starts with "manager: "
+    //   rest of line should parse to a publickey matching expense_manager
+    // third line:
+    //   starts with "treasury: "
+    //   rest of line should parse to a publickey matching squad_treasury
+    let mut description_lines = proposal.description.lines();
+    let withdraw_lamports = next_proposal_field(&mut description_lines, "lamports: ")?
+        .parse::<u64>()
+        .map_err(|_| SlideError::FailedToParseProposal)?;
+    let manager_pubkey =
+        Pubkey::from_str(next_proposal_field(&mut description_lines, "manager: ")?)
+            .map_err(|_| SlideError::FailedToParseProposal)?;
+    require!(
+        manager_pubkey == *expense_manager,
+        SlideError::FailedToParseProposal
+    );
+    let treasury_pubkey =
+        Pubkey::from_str(next_proposal_field(&mut description_lines, "treasury: ")?)
+            .map_err(|_| SlideError::FailedToParseProposal)?;
+    require!(
+        treasury_pubkey == *treasury,
+        SlideError::FailedToParseProposal
+    );
+    Ok(withdraw_lamports)
+}
+
+/// Reads the next line of a proposal description and returns the value after `prefix`
+pub fn next_proposal_field<'a>(lines: &mut std::str::Lines<'a>, prefix: &str) -> Result<&'a str> {
+    let line = lines.next().ok_or(SlideError::FailedToParseProposal)?;
+    require!(line.starts_with(prefix), SlideError::FailedToParseProposal);
+    Ok(line[prefix.len()..].trim_end())
+}
+
+/// Parses a number of seconds from a proposal field, where "none" means no limit
+pub fn parse_optional_seconds(value: &str) -> Result<Option<u32>> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let seconds = value
+        .parse::<u32>()
+        .map_err(|_| SlideError::FailedToParseProposal)?;
+    Ok(Some(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_a_proposal_before_it_starts() {
+        assert_eq!(
+            check_proposal_timing(100, 200, None, 99),
+            Err(SlideError::ProposalNotStarted.into())
+        );
+    }
+
+    #[test]
+    fn accepts_any_age_without_a_window() {
+        assert_eq!(check_proposal_timing(100, 200, None, i64::MAX), Ok(()));
+    }
+
+    #[test]
+    fn accepts_a_proposal_inside_the_window() {
+        assert_eq!(check_proposal_timing(100, 200, Some(60), 200), Ok(()));
+        assert_eq!(check_proposal_timing(100, 200, Some(60), 260), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_proposal_after_the_window() {
+        assert_eq!(
+            check_proposal_timing(100, 200, Some(60), 261),
+            Err(SlideError::ProposalExpired.into())
+        );
+    }
+
+    #[test]
+    fn parses_optional_seconds() {
+        assert_eq!(parse_optional_seconds("none"), Ok(None));
+        assert_eq!(parse_optional_seconds("86400"), Ok(Some(86_400)));
+        assert_eq!(
+            parse_optional_seconds("-1"),
+            Err(SlideError::FailedToParseProposal.into())
+        );
+    }
+}
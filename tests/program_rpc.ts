@@ -1,7 +1,9 @@
 import { Slide } from "../target/types/slide";
 import { Program } from "@project-serum/anchor";
 import {
+  getAuditLogAddressAndBump,
   getExpenseManagerAddressAndBump,
+  getManagerStatsAddressAndBump,
   getUserDataAddressAndBump,
   Payer,
   signers,
@@ -37,12 +39,23 @@ export async function createExpenseManager(
   name: string
 ) {
   const [expenseManagerPDA] = getExpenseManagerAddressAndBump(
+    payer.publicKey,
     name,
     program.programId
   );
+  const [auditLogPDA] = getAuditLogAddressAndBump(
+    expenseManagerPDA,
+    program.programId
+  );
+  const [managerStatsPDA] = getManagerStatsAddressAndBump(
+    expenseManagerPDA,
+    program.programId
+  );
   await program.rpc.createExpenseManager(name, membership_token_mint, {
     accounts: {
       expenseManager: expenseManagerPDA,
+      auditLog: auditLogPDA,
+      managerStats: managerStatsPDA,
       payer: payer.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
     },
@@ -50,5 +63,7 @@ export async function createExpenseManager(
   });
   return {
     expenseManagerPDA,
+    auditLogPDA,
+    managerStatsPDA,
   };
 }
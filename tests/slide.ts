@@ -2,7 +2,12 @@ import * as anchor from "@project-serum/anchor";
 import { Program } from "@project-serum/anchor";
 import { Slide } from "../target/types/slide";
 import { assert, expect } from "chai";
-import { getFundedAccount } from "./utils";
+import {
+  getExpenseManagerAddressAndBump,
+  getFundedAccount,
+  getManagerMetadataAddressAndBump,
+  toBN,
+} from "./utils";
 import { createMint } from "@solana/spl-token";
 import { createExpenseManager, initializeUser } from "./program_rpc";
 
@@ -23,6 +28,38 @@ describe("slide base tests", () => {
     expect(userData.username).to.equal("0x63problems");
     expect(userData.realName).to.equal("me");
     assert(userData.user.equals(user.publicKey));
+    expect(userData.version).to.equal(1);
+  });
+  it("rejects a username longer than its size budget", async () => {
+    const user = await getFundedAccount(program);
+    let error = null;
+    try {
+      await initializeUser(program, user, "x".repeat(65), "me");
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal("DataTooLarge");
+  });
+  it("migrates user data to the current layout", async () => {
+    const user = await getFundedAccount(program);
+    const { userDataPDA } = await initializeUser(
+      program,
+      user,
+      "migrating",
+      "me"
+    );
+    await program.methods
+      .migrateUserData()
+      .accounts({
+        account: userDataPDA,
+        payer: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+
+    let userData = await program.account.userData.fetch(userDataPDA);
+    expect(userData.version).to.equal(1);
+    expect(userData.username).to.equal("migrating");
   });
   it("creates expense manager with correct initial values", async () => {
     const payer = await getFundedAccount(program);
@@ -43,5 +80,179 @@ describe("slide base tests", () => {
       expenseManagerPDA
     );
     expect(expenseManagerData.name).to.equal("testing manager");
+    assert(expenseManagerData.namespace.equals(payer.publicKey));
+  });
+  it("looks up managers with the same name by their namespace", async () => {
+    const payer = await getFundedAccount(program);
+    const otherPayer = await getFundedAccount(program);
+    const membership_token_mint = await createMint(
+      program.provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      9
+    );
+    await createExpenseManager(
+      program,
+      membership_token_mint,
+      payer,
+      "shared name"
+    );
+    await createExpenseManager(
+      program,
+      membership_token_mint,
+      otherPayer,
+      "shared name"
+    );
+
+    const [expenseManager] = getExpenseManagerAddressAndBump(
+      payer.publicKey,
+      "shared name",
+      program.programId
+    );
+    const [otherExpenseManager] = getExpenseManagerAddressAndBump(
+      otherPayer.publicKey,
+      "shared name",
+      program.programId
+    );
+    const [legacyExpenseManager] = getExpenseManagerAddressAndBump(
+      null,
+      "shared name",
+      program.programId
+    );
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    const otherExpenseManagerData =
+      await program.account.expenseManager.fetch(otherExpenseManager);
+    const legacyExpenseManagerData =
+      await program.account.expenseManager.fetchNullable(legacyExpenseManager);
+
+    assert(expenseManagerData.namespace.equals(payer.publicKey));
+    assert(otherExpenseManagerData.namespace.equals(otherPayer.publicKey));
+    expect(legacyExpenseManagerData).to.equal(null);
+  });
+  it("migrates a current expense manager in place", async () => {
+    const payer = await getFundedAccount(program);
+    const membership_token_mint = await createMint(
+      program.provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      9
+    );
+    const { expenseManagerPDA } = await createExpenseManager(
+      program,
+      membership_token_mint,
+      payer,
+      "migrated manager"
+    );
+    const accountPre = await program.provider.connection.getAccountInfo(
+      expenseManagerPDA
+    );
+    await program.methods
+      .migrateExpenseManager()
+      .accounts({
+        account: expenseManagerPDA,
+        payer: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+    const accountPost = await program.provider.connection.getAccountInfo(
+      expenseManagerPDA
+    );
+
+    let expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManagerPDA
+    );
+    expect(accountPost.data.length).to.equal(accountPre.data.length);
+    expect(expenseManagerData.version).to.equal(9);
+  });
+  it("initializes manager metadata with the manager name", async () => {
+    const payer = await getFundedAccount(program);
+    const membership_token_mint = await createMint(
+      program.provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      9
+    );
+    const { expenseManagerPDA } = await createExpenseManager(
+      program,
+      membership_token_mint,
+      payer,
+      "described manager"
+    );
+    const [managerMetadataPDA] = getManagerMetadataAddressAndBump(
+      expenseManagerPDA,
+      program.programId
+    );
+    await program.methods
+      .initializeManagerMetadata()
+      .accounts({
+        managerMetadata: managerMetadataPDA,
+        expenseManager: expenseManagerPDA,
+        payer: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+
+    let metadata = await program.account.expenseManagerMetadata.fetch(
+      managerMetadataPDA
+    );
+    assert(metadata.expenseManager.equals(expenseManagerPDA));
+    expect(metadata.displayName).to.equal("described manager");
+    expect(metadata.description).to.equal("");
+  });
+  it("funds an expense manager and emits an event", async () => {
+    const payer = await getFundedAccount(program);
+    const membership_token_mint = await createMint(
+      program.provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      9
+    );
+    const { expenseManagerPDA, auditLogPDA } = await createExpenseManager(
+      program,
+      membership_token_mint,
+      payer,
+      "funded manager"
+    );
+    const amount = 100_000;
+    let fundedEvent = null;
+    const listener = program.addEventListener(
+      "ExpenseManagerFunded",
+      (event) => {
+        fundedEvent = event;
+      }
+    );
+
+    const balancePre = await program.provider.connection.getBalance(
+      expenseManagerPDA
+    );
+    await program.methods
+      .fundExpenseManager(toBN(amount))
+      .accounts({
+        expenseManager: expenseManagerPDA,
+        auditLog: auditLogPDA,
+        funder: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+    const balancePost = await program.provider.connection.getBalance(
+      expenseManagerPDA
+    );
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect(balancePost - balancePre).to.equal(amount);
+    assert(fundedEvent.expenseManager.equals(expenseManagerPDA));
+    expect(fundedEvent.amount.toNumber()).to.equal(amount);
+
+    const auditLogData = await program.account.auditLog.fetch(auditLogPDA);
+    expect(auditLogData.count.toNumber()).to.equal(1);
+    assert(auditLogData.entries[0].actor.equals(payer.publicKey));
+    expect(auditLogData.entries[0].amount.toNumber()).to.equal(amount);
   });
 });
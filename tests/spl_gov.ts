@@ -27,7 +27,11 @@ import {
   withSignOffProposal,
   withCreateTokenGovernance,
 } from "@solana/spl-governance";
-import { airdropToAccount, getFundedAccount } from "./utils";
+import {
+  airdropToAccount,
+  getFundedAccount,
+  getUserDataAddressAndBump,
+} from "./utils";
 import { SPL_GOV_PROGRAM_ID } from "@slidexyz/slide-sdk/lib/constants";
 import {
   addAccountAsSigner,
@@ -43,8 +47,9 @@ import {
 } from "@slidexyz/slide-sdk/lib/address";
 import { MintMaxVoteWeightSource } from "@solana/spl-governance/lib/governance/accounts";
 import * as anchor from "@project-serum/anchor";
-import { createExpenseManager } from "./program_rpc";
+import { createExpenseManager, initializeUser } from "./program_rpc";
 import { assert, expect } from "chai";
+import { createHash } from "crypto";
 
 async function setupSPLGov(
   program: Program<Slide>,
@@ -220,6 +225,8 @@ type SPLGovSharedData = {
   governance?: PublicKey;
   nativeTreasury?: PublicKey;
   expenseManager?: PublicKey;
+  auditLog?: PublicKey;
+  managerStats?: PublicKey;
   accessRecord?: PublicKey;
   expensePackage?: PublicKey;
   packageNonce?: number;
@@ -258,12 +265,13 @@ describe("slide SPL Governance integration tests", () => {
   });
   it("creates and initializes an expense manager", async () => {
     const { user, realm, membershipTokenMint, tokenOwnerRecord } = sharedData;
-    const { expenseManagerPDA } = await createExpenseManager(
-      program,
-      membershipTokenMint,
-      user,
-      managerName
-    );
+    const { expenseManagerPDA, auditLogPDA, managerStatsPDA } =
+      await createExpenseManager(
+        program,
+        membershipTokenMint,
+        user,
+        managerName
+      );
     await airdropToAccount(program, expenseManagerPDA);
     const { governance, nativeTreasury } = await createExpenseGovernance(
       program,
@@ -287,6 +295,8 @@ describe("slide SPL Governance integration tests", () => {
     );
 
     sharedData.expenseManager = expenseManagerPDA;
+    sharedData.auditLog = auditLogPDA;
+    sharedData.managerStats = managerStatsPDA;
     sharedData.governance = governance;
     sharedData.nativeTreasury = nativeTreasury;
 
@@ -295,24 +305,34 @@ describe("slide SPL Governance integration tests", () => {
     assert(expenseManager.governanceAuthority.equals(governance));
   });
   it("creates an expense package", async () => {
-    const { user, realm, tokenOwnerRecord, expenseManager } = sharedData;
+    const { user, realm, tokenOwnerRecord, expenseManager, managerStats } =
+      sharedData;
     const [expensePackagePDA, packageBump] = getExpensePackageAddressAndBump(
       expenseManager,
       user.publicKey,
       0,
       program.programId
     );
+    const { userDataPDA: userData } = await initializeUser(
+      program,
+      user,
+      "owner",
+      "Expense Owner"
+    );
     await program.methods
       .splGovCreateExpensePackage(
         realm,
         0,
         packageName,
         packageDescription,
-        packageQuantity
+        packageQuantity,
+        null
       )
       .accounts({
         expensePackage: expensePackagePDA,
+        userData,
         expenseManager,
+        managerStats,
         tokenOwnerRecord,
         owner: user.publicKey,
       })
@@ -322,16 +342,14 @@ describe("slide SPL Governance integration tests", () => {
     const expensePackageData = await program.account.expensePackage.fetch(
       expensePackagePDA
     );
-    const expenseManagerData = await program.account.expenseManager.fetch(
-      expenseManager
-    );
+    const userDataAccount = await program.account.userData.fetch(userData);
 
     sharedData.expensePackage = expensePackagePDA;
     sharedData.packageNonce = 0;
 
     expect(expensePackageData.bump).to.equal(packageBump);
     expect(expensePackageData.state).to.eql({ created: {} });
-    expect(expenseManagerData.expensePackageNonce).to.equal(1);
+    expect(userDataAccount.expensePackageNonce).to.equal(1);
   });
   it("updates an expense package", async () => {
     const {
@@ -377,12 +395,16 @@ describe("slide SPL Governance integration tests", () => {
       expenseManager,
       expensePackage,
       packageNonce,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
-      .splGovSubmitExpensePackage(realm, packageNonce)
+      .splGovSubmitExpensePackage(realm, packageNonce, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         tokenOwnerRecord,
         owner: user.publicKey,
       })
@@ -393,7 +415,14 @@ describe("slide SPL Governance integration tests", () => {
       expensePackage
     );
 
+    const managerStatsData = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+
     expect(expensePackageData.state).to.eql({ pending: {} });
+    expect(managerStatsData.createdPackages.toNumber()).to.equal(0);
+    expect(managerStatsData.pendingPackages.toNumber()).to.equal(1);
+    expect(managerStatsData.version).to.equal(1);
   });
   it("grants reviewer access", async () => {
     // generate instructions for creating an access record
@@ -407,6 +436,7 @@ describe("slide SPL Governance integration tests", () => {
       membershipTokenMint,
       governance,
       nativeTreasury,
+      auditLog,
     } = sharedData;
     const [accessRecord] = getAccessRecordAddressAndBump(
       program.programId,
@@ -420,6 +450,7 @@ describe("slide SPL Governance integration tests", () => {
       .accounts({
         accessRecord,
         expenseManager,
+        auditLog,
         governanceAuthority: governance,
         nativeTreasury,
       })
@@ -532,6 +563,7 @@ describe("slide SPL Governance integration tests", () => {
   });
   it("approves expense package", async () => {
     const {
+      user,
       reviewer,
       reviewerTokenOwnerRecord,
       expensePackage,
@@ -539,15 +571,20 @@ describe("slide SPL Governance integration tests", () => {
       packageNonce,
       realm,
       accessRecord,
+      auditLog,
+      managerStats,
     } = sharedData;
 
     await program.methods
-      .splGovApproveExpensePackage(realm, packageNonce)
+      .splGovApproveExpensePackage(realm, packageNonce, null, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         tokenOwnerRecord: reviewerTokenOwnerRecord,
         accessRecord,
+        payee: user.publicKey,
         authority: reviewer.publicKey,
       })
       .signers(signers(program, [reviewer]))
@@ -559,8 +596,47 @@ describe("slide SPL Governance integration tests", () => {
 
     expect(expensePackageData.state).to.eql({ approved: {} });
   });
+  it("rejects a withdrawal signed by a non-owner", async () => {
+    const {
+      user,
+      expenseManager,
+      expensePackage,
+      packageNonce,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const impostor = await getFundedAccount(program);
+    let error = null;
+    try {
+      await program.methods
+        .withdrawFromExpensePackage(packageNonce)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          payee: user.publicKey,
+          // only checked for managers governed by a squad
+          squad: expenseManager,
+          owner: impostor.publicKey,
+        })
+        .signers(signers(program, [impostor]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error?.error?.errorCode?.code).to.equal("ConstraintHasOne");
+  });
   it("withdraws from expense package", async () => {
-    const { user, expensePackage, packageNonce } = sharedData;
+    const {
+      user,
+      expenseManager,
+      expensePackage,
+      packageNonce,
+      auditLog,
+      managerStats,
+    } = sharedData;
 
     const userBalancePre = await getBalance(connection, user.publicKey);
     const packageBalancePre = await getBalance(connection, expensePackage);
@@ -569,6 +645,12 @@ describe("slide SPL Governance integration tests", () => {
       .withdrawFromExpensePackage(packageNonce)
       .accounts({
         expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        payee: user.publicKey,
+        // only checked for managers governed by a squad
+        squad: expenseManager,
         owner: user.publicKey,
       })
       .signers(signers(program, [user]))
@@ -590,24 +672,32 @@ describe("slide SPL Governance integration tests", () => {
     expect(packageData.state).to.eql({ paid: {} });
   });
   it("creates second expense package", async () => {
-    const { user, realm, tokenOwnerRecord, expenseManager } = sharedData;
+    const { user, realm, tokenOwnerRecord, expenseManager, managerStats } =
+      sharedData;
     const [expensePackagePDA, packageBump] = getExpensePackageAddressAndBump(
       expenseManager,
       user.publicKey,
       1,
       program.programId
     );
+    const [userData] = getUserDataAddressAndBump(
+      user.publicKey,
+      program.programId
+    );
     await program.methods
       .splGovCreateExpensePackage(
         realm,
         1,
         packageName,
         packageDescription,
-        packageQuantity
+        packageQuantity,
+        null
       )
       .accounts({
         expensePackage: expensePackagePDA,
+        userData,
         expenseManager,
+        managerStats,
         tokenOwnerRecord,
         owner: user.publicKey,
       })
@@ -617,16 +707,145 @@ describe("slide SPL Governance integration tests", () => {
     const expensePackageData = await program.account.expensePackage.fetch(
       expensePackagePDA
     );
-    const expenseManagerData = await program.account.expenseManager.fetch(
-      expenseManager
-    );
+    const userDataAccount = await program.account.userData.fetch(userData);
 
     sharedData.expensePackage = expensePackagePDA;
     sharedData.packageNonce = 1;
 
     expect(expensePackageData.bump).to.equal(packageBump);
     expect(expensePackageData.state).to.eql({ created: {} });
-    expect(expenseManagerData.expensePackageNonce).to.equal(2);
+    expect(userDataAccount.expensePackageNonce).to.equal(2);
+  });
+  it("creates user data with a member's first expense package", async () => {
+    const {
+      reviewer,
+      realm,
+      reviewerTokenOwnerRecord,
+      expenseManager,
+      managerStats,
+    } = sharedData;
+    const [expensePackagePDA] = getExpensePackageAddressAndBump(
+      expenseManager,
+      reviewer.publicKey,
+      0,
+      program.programId
+    );
+    const [userData, userDataBump] = getUserDataAddressAndBump(
+      reviewer.publicKey,
+      program.programId
+    );
+    await airdropToAccount(program, reviewer.publicKey);
+    await program.methods
+      .splGovCreateExpensePackage(
+        realm,
+        0,
+        packageName,
+        packageDescription,
+        packageQuantity,
+        null
+      )
+      .accounts({
+        expensePackage: expensePackagePDA,
+        userData,
+        expenseManager,
+        managerStats,
+        tokenOwnerRecord: reviewerTokenOwnerRecord,
+        owner: reviewer.publicKey,
+      })
+      .signers(signers(program, [reviewer]))
+      .rpc();
+
+    const userDataAccount = await program.account.userData.fetch(userData);
+
+    expect(userDataAccount.bump).to.equal(userDataBump);
+    assert(userDataAccount.user.equals(reviewer.publicKey));
+    expect(userDataAccount.expensePackageNonce).to.equal(1);
+    expect(userDataAccount.version).to.equal(1);
+  });
+  it("fills in user data created with a first expense package", async () => {
+    const { reviewer } = sharedData;
+    const { userDataPDA: userData } = await initializeUser(
+      program,
+      reviewer,
+      "reviewer",
+      "Expense Reviewer"
+    );
+    let error = null;
+    try {
+      await initializeUser(program, reviewer, "impostor", "Someone Else");
+    } catch (e) {
+      error = e;
+    }
+
+    const userDataAccount = await program.account.userData.fetch(userData);
+
+    expect(userDataAccount.username).to.equal("reviewer");
+    expect(userDataAccount.realName).to.equal("Expense Reviewer");
+    expect(userDataAccount.expensePackageNonce).to.equal(1);
+    expect(error?.error?.errorCode?.code).to.equal("UserAlreadyInitialized");
+  });
+  it("itemizes second expense package", async () => {
+    const { user, expensePackage, packageNonce } = sharedData;
+    const lineItemAmounts = [100_000, 250_000];
+    for (const amount of lineItemAmounts) {
+      await program.methods
+        .addExpenseLineItem(
+          packageNonce,
+          "SPLGOVINTEGRATIONTESTLINEITEM",
+          toBN(amount),
+          "travel",
+          toBN(Math.floor(Date.now() / 1000))
+        )
+        .accounts({
+          expensePackage,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    }
+    await program.methods
+      .removeExpenseLineItem(packageNonce, 0)
+      .accounts({
+        expensePackage,
+        owner: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+
+    expect(expensePackageData.lineItems.length).to.equal(1);
+    expect(expensePackageData.lineItems[0].category).to.equal("travel");
+    expect(expensePackageData.quantity.toNumber()).to.equal(250_000);
+  });
+  it("attaches a receipt to second expense package", async () => {
+    const { user, expensePackage, packageNonce } = sharedData;
+    const contentHash = Array.from(
+      createHash("sha256").update("SPLGOVINTEGRATIONTESTRECEIPT").digest()
+    );
+    await program.methods
+      .addExpenseAttachment(
+        packageNonce,
+        { ipfs: {} },
+        contentHash,
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+      )
+      .accounts({
+        expensePackage,
+        owner: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+
+    expect(expensePackageData.attachments.length).to.equal(1);
+    expect(expensePackageData.attachments[0].scheme).to.eql({ ipfs: {} });
+    expect(expensePackageData.attachments[0].contentHash).to.eql(contentHash);
   });
   it("submits second expense package", async () => {
     const {
@@ -636,12 +855,16 @@ describe("slide SPL Governance integration tests", () => {
       expenseManager,
       expensePackage,
       packageNonce,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
-      .splGovSubmitExpensePackage(realm, packageNonce)
+      .splGovSubmitExpensePackage(realm, packageNonce, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         tokenOwnerRecord,
         owner: user.publicKey,
       })
@@ -663,6 +886,8 @@ describe("slide SPL Governance integration tests", () => {
       packageNonce,
       realm,
       accessRecord,
+      auditLog,
+      managerStats,
     } = sharedData;
 
     await program.methods
@@ -670,6 +895,8 @@ describe("slide SPL Governance integration tests", () => {
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         tokenOwnerRecord: reviewerTokenOwnerRecord,
         accessRecord,
         authority: reviewer.publicKey,
@@ -693,12 +920,16 @@ describe("slide SPL Governance integration tests", () => {
       membershipTokenMint,
       governance,
       nativeTreasury,
+      auditLog,
+      managerStats,
     } = sharedData;
     const withdrawalAmount = LAMPORTS_PER_SOL;
     const instruction: TransactionInstruction = await program.methods
       .splGovWithdrawFromExpenseManager(realm, new BN(withdrawalAmount))
       .accounts({
         expenseManager,
+        auditLog,
+        managerStats,
         governanceAuthority: governance,
         nativeTreasury,
       })
@@ -13,7 +13,7 @@ import {
   getProposalExecutionAddressAndBump,
 } from "@slidexyz/slide-sdk/lib/address";
 import * as anchor from "@project-serum/anchor";
-import { createExpenseManager } from "./program_rpc";
+import { createExpenseManager, initializeUser } from "./program_rpc";
 import { assert, expect } from "chai";
 import {
   SQUADS_PROGRAM_ID,
@@ -24,7 +24,15 @@ import {
   withCastVote,
   getSquadTreasuryAddressAndBump,
 } from "@slidexyz/squads-sdk";
-import { airdropToAccount, getFundedAccount } from "./utils";
+import {
+  airdropToAccount,
+  getFundedAccount,
+  getExpenseManagerAddressAndBump,
+  getManagerMetadataAddressAndBump,
+  getProposalWatermarkAddressAndBump,
+  getRecurringExpenseAddressAndBump,
+  getUserDataAddressAndBump,
+} from "./utils";
 
 async function setupSquad(
   program: Program<Slide>,
@@ -61,12 +69,13 @@ async function setupSquad(
   return { squad, squadMint, squadSol, randomId, reviewer };
 }
 
-async function createReviewerAccessProposal(
+async function createAccessProposal(
   program: Program<Slide>,
   user: Keypair,
-  reviewer: PublicKey,
+  member: PublicKey,
   squad: PublicKey,
-  nonce: number
+  nonce: number,
+  role = "reviewer"
 ) {
   let instructions = [];
   const { proposal } = await withCreateProposalAccount(
@@ -77,7 +86,7 @@ async function createReviewerAccessProposal(
     nonce,
     0,
     "[SLIDE PROPOSAL] Grant Permissions",
-    `member: ${reviewer.toString()}\nrole: reviewer`,
+    `member: ${member.toString()}\nrole: ${role}`,
     2,
     ["Approve", "Deny"]
   );
@@ -122,6 +131,134 @@ async function createWithdrawalProposal(
   return { proposal };
 }
 
+async function createCloseProposal(
+  program: Program<Slide>,
+  user: Keypair,
+  squad: PublicKey,
+  expenseManager: PublicKey,
+  nonce: number
+) {
+  let instructions = [];
+  const { proposal } = await withCreateProposalAccount(
+    instructions,
+    SQUADS_PROGRAM_ID,
+    user.publicKey,
+    squad,
+    nonce,
+    0,
+    "[SLIDE PROPOSAL] Close Manager",
+    `close manager: ${expenseManager.toString()}`,
+    2,
+    ["Approve", "Deny"]
+  );
+
+  const txn = new Transaction();
+  txn.add(...instructions);
+  await program.provider.send(txn, signers(program, [user]));
+
+  return { proposal };
+}
+
+async function createUnpauseProposal(
+  program: Program<Slide>,
+  user: Keypair,
+  squad: PublicKey,
+  expenseManager: PublicKey,
+  nonce: number
+) {
+  let instructions = [];
+  const { proposal } = await withCreateProposalAccount(
+    instructions,
+    SQUADS_PROGRAM_ID,
+    user.publicKey,
+    squad,
+    nonce,
+    0,
+    "[SLIDE PROPOSAL] Unpause Manager",
+    `unpause manager: ${expenseManager.toString()}`,
+    2,
+    ["Approve", "Deny"]
+  );
+
+  const txn = new Transaction();
+  txn.add(...instructions);
+  await program.provider.send(txn, signers(program, [user]));
+
+  return { proposal };
+}
+
+// setting is "execution window" or "withdrawal timelock"
+async function createManagerSecondsProposal(
+  program: Program<Slide>,
+  user: Keypair,
+  squad: PublicKey,
+  expenseManager: PublicKey,
+  setting: string,
+  seconds: number | null,
+  nonce: number
+) {
+  let instructions = [];
+  const { proposal } = await withCreateProposalAccount(
+    instructions,
+    SQUADS_PROGRAM_ID,
+    user.publicKey,
+    squad,
+    nonce,
+    0,
+    `[SLIDE PROPOSAL] Set ${setting}`,
+    `set ${setting}: ${expenseManager.toString()}\n` +
+      `seconds: ${seconds ?? "none"}`,
+    2,
+    ["Approve", "Deny"]
+  );
+
+  const txn = new Transaction();
+  txn.add(...instructions);
+  await program.provider.send(txn, signers(program, [user]));
+
+  return { proposal };
+}
+
+async function createRecurringExpenseProposal(
+  program: Program<Slide>,
+  user: Keypair,
+  squad: PublicKey,
+  recurringExpense: PublicKey,
+  nonce: number
+) {
+  let instructions = [];
+  const { proposal } = await withCreateProposalAccount(
+    instructions,
+    SQUADS_PROGRAM_ID,
+    user.publicKey,
+    squad,
+    nonce,
+    0,
+    "[SLIDE PROPOSAL] Approve Recurring Expense",
+    `approve recurring expense: ${recurringExpense.toString()}`,
+    2,
+    ["Approve", "Deny"]
+  );
+
+  const txn = new Transaction();
+  txn.add(...instructions);
+  await program.provider.send(txn, signers(program, [user]));
+
+  return { proposal };
+}
+
+function getProposalAddress(squad: PublicKey, index: number) {
+  const [proposal] = anchor.utils.publicKey.findProgramAddressSync(
+    [
+      squad.toBuffer(),
+      new BN(index).toArrayLike(Buffer, "le", 4),
+      Buffer.from("!proposal"),
+    ],
+    SQUADS_PROGRAM_ID
+  );
+  return proposal;
+}
+
 async function castVoteOnProposal(
   program: Program<Slide>,
   user: Keypair,
@@ -146,6 +283,185 @@ async function castVoteOnProposal(
   return { voteAccount };
 }
 
+async function submitExpensePackage(
+  program: Program<Slide>,
+  sharedData: SquadsSharedData,
+  nonce: number,
+  quantity: BN,
+  advance = false,
+  category: number | null = null
+) {
+  const {
+    user,
+    squad,
+    memberEquityRecord,
+    expenseManager,
+    auditLog,
+    managerStats,
+  } = sharedData;
+  const [expensePackage] = getExpensePackageAddressAndBump(
+    expenseManager,
+    user.publicKey,
+    nonce,
+    program.programId
+  );
+  const [userData] = getUserDataAddressAndBump(
+    user.publicKey,
+    program.programId
+  );
+  await program.methods
+    .squadsCreateExpensePackage(
+      nonce,
+      "SQUADSTESTPACKAGE",
+      "SQUADSTESTPACKAGEDESCRIPTION",
+      quantity,
+      category
+    )
+    .accounts({
+      expensePackage,
+      userData,
+      expenseManager,
+      managerStats,
+      memberEquity: memberEquityRecord,
+      squad,
+      owner: user.publicKey,
+    })
+    .signers(signers(program, [user]))
+    .rpc();
+  if (advance) {
+    await program.methods
+      .setExpenseAdvance(nonce, true)
+      .accounts({ expensePackage, owner: user.publicKey })
+      .signers(signers(program, [user]))
+      .rpc();
+  }
+  await program.methods
+    .squadsSubmitExpensePackage(nonce, null)
+    .accounts({
+      expensePackage,
+      expenseManager,
+      auditLog,
+      managerStats,
+      squad,
+      memberEquity: memberEquityRecord,
+      owner: user.publicKey,
+    })
+    .signers(signers(program, [user]))
+    .rpc();
+
+  return { expensePackage };
+}
+
+async function approveExpensePackage(
+  program: Program<Slide>,
+  sharedData: SquadsSharedData,
+  nonce: number,
+  approvedAmount: BN | null = null,
+  vesting: { start: BN; cliff: BN; end: BN } | null = null
+) {
+  const {
+    user,
+    reviewer,
+    squad,
+    reviewerMemberEquityRecord,
+    expenseManager,
+    accessRecord,
+    auditLog,
+    managerStats,
+  } = sharedData;
+  const [expensePackage] = getExpensePackageAddressAndBump(
+    expenseManager,
+    user.publicKey,
+    nonce,
+    program.programId
+  );
+  await program.methods
+    .squadsApproveExpensePackage(nonce, approvedAmount, vesting)
+    .accounts({
+      expensePackage,
+      expenseManager,
+      auditLog,
+      managerStats,
+      accessRecord,
+      memberEquity: reviewerMemberEquityRecord,
+      squad,
+      payee: user.publicKey,
+      authority: reviewer.publicKey,
+    })
+    .signers(signers(program, [reviewer]))
+    .rpc();
+
+  return { expensePackage };
+}
+
+async function setWithdrawalDeadline(
+  program: Program<Slide>,
+  sharedData: SquadsSharedData,
+  withdrawalDeadline: number
+) {
+  const { user, expenseManager } = sharedData;
+  const [adminAccessRecord] = getAccessRecordAddressAndBump(
+    program.programId,
+    expenseManager,
+    user.publicKey
+  );
+  await program.methods
+    .setPackageDeadlines(null, null, withdrawalDeadline)
+    .accounts({
+      expenseManager,
+      accessRecord: adminAccessRecord,
+      authority: user.publicKey,
+    })
+    .signers(signers(program, [user]))
+    .rpc();
+}
+
+async function addExpenseCategory(
+  program: Program<Slide>,
+  sharedData: SquadsSharedData,
+  name: string,
+  budget: BN | null,
+  reviewers: PublicKey[]
+) {
+  const { user, expenseManager } = sharedData;
+  const [adminAccessRecord] = getAccessRecordAddressAndBump(
+    program.programId,
+    expenseManager,
+    user.publicKey
+  );
+  await program.methods
+    .addExpenseCategory(name, budget, reviewers)
+    .accounts({
+      expenseManager,
+      accessRecord: adminAccessRecord,
+      authority: user.publicKey,
+    })
+    .signers(signers(program, [user]))
+    .rpc();
+}
+
+async function setDirectPayout(
+  program: Program<Slide>,
+  sharedData: SquadsSharedData,
+  directPayout: boolean
+) {
+  const { user, expenseManager } = sharedData;
+  const [adminAccessRecord] = getAccessRecordAddressAndBump(
+    program.programId,
+    expenseManager,
+    user.publicKey
+  );
+  await program.methods
+    .setDirectPayout(directPayout)
+    .accounts({
+      expenseManager,
+      accessRecord: adminAccessRecord,
+      authority: user.publicKey,
+    })
+    .signers(signers(program, [user]))
+    .rpc();
+}
+
 type SquadsSharedData = {
   user?: Keypair;
   reviewer?: Keypair;
@@ -155,6 +471,8 @@ type SquadsSharedData = {
   memberEquityRecord?: PublicKey;
   reviewerMemberEquityRecord?: PublicKey;
   expenseManager?: PublicKey;
+  auditLog?: PublicKey;
+  managerStats?: PublicKey;
   accessRecord?: PublicKey;
   expensePackage?: PublicKey;
   packageNonce?: number;
@@ -206,11 +524,15 @@ describe("slide Squads integration tests", () => {
   });
   it("creates and initializes an expense manager", async () => {
     const { user, squad, squadMint, memberEquityRecord } = sharedData;
-    const { expenseManagerPDA: expenseManager } = await createExpenseManager(
-      program,
-      squadMint,
-      user,
-      managerName
+    const {
+      expenseManagerPDA: expenseManager,
+      auditLogPDA: auditLog,
+      managerStatsPDA: managerStats,
+    } = await createExpenseManager(program, squadMint, user, managerName);
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
     );
     await program.methods
       .squadsInitializeExpenseManager(SQUADS_PROGRAM_ID)
@@ -218,6 +540,7 @@ describe("slide Squads integration tests", () => {
         expenseManager,
         memberEquity: memberEquityRecord,
         squad,
+        proposalWatermark,
         member: user.publicKey,
       })
       .signers(signers(program, [user]))
@@ -231,6 +554,8 @@ describe("slide Squads integration tests", () => {
     );
 
     sharedData.expenseManager = expenseManager;
+    sharedData.auditLog = auditLog;
+    sharedData.managerStats = managerStats;
 
     assert(expenseManagerData.externalProgramId.equals(SQUADS_PROGRAM_ID));
     assert(expenseManagerData.membershipTokenMint.equals(squadMint));
@@ -238,23 +563,33 @@ describe("slide Squads integration tests", () => {
     expect(expenseManagerData.name).to.equal(managerName);
   });
   it("creates an expense package", async () => {
-    const { user, squad, memberEquityRecord, expenseManager } = sharedData;
+    const { user, squad, memberEquityRecord, expenseManager, managerStats } =
+      sharedData;
     const [expensePackagePDA, packageBump] = getExpensePackageAddressAndBump(
       expenseManager,
       user.publicKey,
       0,
       program.programId
     );
+    const { userDataPDA: userData } = await initializeUser(
+      program,
+      user,
+      "owner",
+      "Expense Owner"
+    );
     await program.methods
       .squadsCreateExpensePackage(
         0,
         packageName,
         packageDescription,
-        packageQuantity
+        packageQuantity,
+        null
       )
       .accounts({
         expensePackage: expensePackagePDA,
+        userData,
         expenseManager,
+        managerStats,
         memberEquity: memberEquityRecord,
         squad,
         owner: user.publicKey,
@@ -265,16 +600,14 @@ describe("slide Squads integration tests", () => {
     const expensePackageData = await program.account.expensePackage.fetch(
       expensePackagePDA
     );
-    const expenseManagerData = await program.account.expenseManager.fetch(
-      expenseManager
-    );
+    const userDataAccount = await program.account.userData.fetch(userData);
 
     sharedData.expensePackage = expensePackagePDA;
     sharedData.packageNonce = 0;
 
     expect(expensePackageData.bump).to.equal(packageBump);
     expect(expensePackageData.state).to.eql({ created: {} });
-    expect(expenseManagerData.expensePackageNonce).to.equal(1);
+    expect(userDataAccount.expensePackageNonce).to.equal(1);
   });
   it("updates an expense package", async () => {
     const {
@@ -320,12 +653,16 @@ describe("slide Squads integration tests", () => {
       expenseManager,
       expensePackage,
       packageNonce,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
-      .squadsSubmitExpensePackage(packageNonce)
+      .squadsSubmitExpensePackage(packageNonce, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         squad,
         memberEquity: memberEquityRecord,
         owner: user.publicKey,
@@ -340,9 +677,10 @@ describe("slide Squads integration tests", () => {
     expect(expensePackageData.state).to.eql({ pending: {} });
   });
   it("grants reviewer access", async () => {
-    const { user, reviewer, squad, squadMint, expenseManager } = sharedData;
+    const { user, reviewer, squad, squadMint, expenseManager, auditLog } =
+      sharedData;
     // creates a free text proposal
-    const { proposal } = await createReviewerAccessProposal(
+    const { proposal } = await createAccessProposal(
       program,
       user,
       reviewer.publicKey,
@@ -370,6 +708,7 @@ describe("slide Squads integration tests", () => {
         proposal,
         accessRecord,
         expenseManager,
+        auditLog,
         squad,
         squadMint,
         proposalExecution,
@@ -391,6 +730,7 @@ describe("slide Squads integration tests", () => {
   });
   it("approves expense package", async () => {
     const {
+      user,
       reviewer,
       expensePackage,
       expenseManager,
@@ -398,15 +738,20 @@ describe("slide Squads integration tests", () => {
       accessRecord,
       squad,
       reviewerMemberEquityRecord,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
-      .squadsApproveExpensePackage(packageNonce)
+      .squadsApproveExpensePackage(packageNonce, null, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         accessRecord,
         memberEquity: reviewerMemberEquityRecord,
         squad,
+        payee: user.publicKey,
         authority: reviewer.publicKey,
       })
       .signers(signers(program, [reviewer]))
@@ -419,7 +764,15 @@ describe("slide Squads integration tests", () => {
     expect(expensePackageData.state).to.eql({ approved: {} });
   });
   it("withdraws from expense package", async () => {
-    const { user, expensePackage, packageNonce } = sharedData;
+    const {
+      user,
+      squad,
+      expenseManager,
+      expensePackage,
+      packageNonce,
+      auditLog,
+      managerStats,
+    } = sharedData;
 
     const userBalancePre = await getBalance(connection, user.publicKey);
     const packageBalancePre = await getBalance(connection, expensePackage);
@@ -428,6 +781,11 @@ describe("slide Squads integration tests", () => {
       .withdrawFromExpensePackage(packageNonce)
       .accounts({
         expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        payee: user.publicKey,
+        squad,
         owner: user.publicKey,
       })
       .signers(signers(program, [user]))
@@ -449,23 +807,31 @@ describe("slide Squads integration tests", () => {
     expect(packageData.state).to.eql({ paid: {} });
   });
   it("creates second expense package", async () => {
-    const { user, squad, memberEquityRecord, expenseManager } = sharedData;
+    const { user, squad, memberEquityRecord, expenseManager, managerStats } =
+      sharedData;
     const [expensePackagePDA, packageBump] = getExpensePackageAddressAndBump(
       expenseManager,
       user.publicKey,
       1,
       program.programId
     );
+    const [userData] = getUserDataAddressAndBump(
+      user.publicKey,
+      program.programId
+    );
     await program.methods
       .squadsCreateExpensePackage(
         1,
         packageName,
         packageDescription,
-        packageQuantity
+        packageQuantity,
+        null
       )
       .accounts({
         expensePackage: expensePackagePDA,
+        userData,
         expenseManager,
+        managerStats,
         memberEquity: memberEquityRecord,
         squad,
         owner: user.publicKey,
@@ -476,16 +842,14 @@ describe("slide Squads integration tests", () => {
     const expensePackageData = await program.account.expensePackage.fetch(
       expensePackagePDA
     );
-    const expenseManagerData = await program.account.expenseManager.fetch(
-      expenseManager
-    );
+    const userDataAccount = await program.account.userData.fetch(userData);
 
     sharedData.expensePackage = expensePackagePDA;
     sharedData.packageNonce = 1;
 
     expect(expensePackageData.bump).to.equal(packageBump);
     expect(expensePackageData.state).to.eql({ created: {} });
-    expect(expenseManagerData.expensePackageNonce).to.equal(2);
+    expect(userDataAccount.expensePackageNonce).to.equal(2);
   });
   it("submits second expense package", async () => {
     const {
@@ -495,12 +859,16 @@ describe("slide Squads integration tests", () => {
       expenseManager,
       expensePackage,
       packageNonce,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
-      .squadsSubmitExpensePackage(packageNonce)
+      .squadsSubmitExpensePackage(packageNonce, null)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         squad,
         memberEquity: memberEquityRecord,
         owner: user.publicKey,
@@ -523,12 +891,16 @@ describe("slide Squads integration tests", () => {
       accessRecord,
       squad,
       reviewerMemberEquityRecord,
+      auditLog,
+      managerStats,
     } = sharedData;
     await program.methods
       .squadsDenyExpensePackage(packageNonce)
       .accounts({
         expensePackage,
         expenseManager,
+        auditLog,
+        managerStats,
         accessRecord,
         memberEquity: reviewerMemberEquityRecord,
         squad,
@@ -543,8 +915,111 @@ describe("slide Squads integration tests", () => {
 
     expect(expensePackageData.state).to.eql({ denied: {} });
   });
+  it("approves expense packages in a batch", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      memberEquityRecord,
+      reviewerMemberEquityRecord,
+      expenseManager,
+      accessRecord,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const [userData] = getUserDataAddressAndBump(
+      user.publicKey,
+      program.programId
+    );
+    const nonces = [2, 3];
+    const expensePackages = [];
+    for (const nonce of nonces) {
+      const [expensePackage] = getExpensePackageAddressAndBump(
+        expenseManager,
+        user.publicKey,
+        nonce,
+        program.programId
+      );
+      await program.methods
+        .squadsCreateExpensePackage(
+          nonce,
+          packageName,
+          packageDescription,
+          packageQuantity,
+          null
+        )
+        .accounts({
+          expensePackage,
+          userData,
+          expenseManager,
+          managerStats,
+          memberEquity: memberEquityRecord,
+          squad,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+      await program.methods
+        .squadsSubmitExpensePackage(nonce, null)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          squad,
+          memberEquity: memberEquityRecord,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+      expensePackages.push(expensePackage);
+    }
+
+    await program.methods
+      .squadsApproveExpensePackages(nonces, nonces.map(() => null))
+      .accounts({
+        expenseManager,
+        auditLog,
+        managerStats,
+        accessRecord,
+        memberEquity: reviewerMemberEquityRecord,
+        squad,
+        authority: reviewer.publicKey,
+      })
+      .remainingAccounts(
+        expensePackages.flatMap((expensePackage) => [
+          { pubkey: expensePackage, isWritable: true, isSigner: false },
+          { pubkey: user.publicKey, isWritable: true, isSigner: false },
+        ])
+      )
+      .signers(signers(program, [reviewer]))
+      .rpc();
+
+    for (const expensePackage of expensePackages) {
+      const expensePackageData = await program.account.expensePackage.fetch(
+        expensePackage
+      );
+      expect(expensePackageData.state).to.eql({ approved: {} });
+    }
+
+    const managerStatsData = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    expect(managerStatsData.pendingPackages.toNumber()).to.equal(0);
+    expect(managerStatsData.approvedPackages.toNumber()).to.equal(2);
+    expect(managerStatsData.deniedPackages.toNumber()).to.equal(1);
+    expect(managerStatsData.paidPackages.toNumber()).to.equal(1);
+  });
   it("withdraws from expense manager", async () => {
-    const { user, squad, squadSol, squadMint, expenseManager } = sharedData;
+    const {
+      user,
+      squad,
+      squadSol,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+    } = sharedData;
     const withdrawalAmount = LAMPORTS_PER_SOL;
 
     // creates a free text proposal
@@ -573,6 +1048,8 @@ describe("slide Squads integration tests", () => {
       .accounts({
         proposal,
         expenseManager,
+        auditLog,
+        managerStats,
         squad,
         squadMint,
         proposalExecution,
@@ -588,4 +1065,1602 @@ describe("slide Squads integration tests", () => {
     expect(managerBalancePre - managerBalancePost).to.equal(withdrawalAmount);
     expect(treasuryBalancePost - treasuryBalancePre).to.equal(withdrawalAmount);
   });
+  it("refuses to close a manager with outstanding packages", async () => {
+    const {
+      user,
+      squad,
+      squadSol,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+      accessRecord,
+    } = sharedData;
+    const { proposal } = await createCloseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      3
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    let error = null;
+    try {
+      await program.methods
+        .squadsExecuteCloseProposal([])
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          auditLog,
+          managerStats,
+          managerMetadata,
+          squad,
+          squadMint,
+          squadTreasury: squadSol,
+          proposalWatermark,
+          signer: user.publicKey,
+        })
+        .remainingAccounts([
+          { pubkey: accessRecord, isWritable: true, isSigner: false },
+        ])
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    // the two packages approved in a batch are still owed to their owner
+    expect(error?.error?.errorCode?.code).to.equal(
+      "OutstandingExpensePackages"
+    );
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    expect(expenseManagerData.accessRecordCount).to.equal(1);
+  });
+  it("blocks payouts while the manager is paused", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+      accessRecord,
+    } = sharedData;
+    const { proposal } = await createAccessProposal(
+      program,
+      user,
+      user.publicKey,
+      squad,
+      4,
+      "admin"
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [adminAccessRecord] = getAccessRecordAddressAndBump(
+      program.programId,
+      expenseManager,
+      user.publicKey
+    );
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    await program.methods
+      .squadsExecuteAccessProposal()
+      .accounts({
+        proposal,
+        accessRecord: adminAccessRecord,
+        expenseManager,
+        auditLog,
+        squad,
+        squadMint,
+        proposalExecution,
+        member: user.publicKey,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    await program.methods
+      .pauseExpenseManager()
+      .accounts({
+        expenseManager,
+        accessRecord: adminAccessRecord,
+        authority: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const [expensePackage] = getExpensePackageAddressAndBump(
+      expenseManager,
+      user.publicKey,
+      2,
+      program.programId
+    );
+    let withdrawError = null;
+    try {
+      await program.methods
+        .withdrawFromExpensePackage(2)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          payee: user.publicKey,
+          squad,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      withdrawError = e;
+    }
+    let payError = null;
+    try {
+      await program.methods
+        .payExpensePackages([2])
+        .accounts({
+          expenseManager,
+          auditLog,
+          managerStats,
+          accessRecord,
+          squad,
+          authority: reviewer.publicKey,
+        })
+        .remainingAccounts([
+          { pubkey: expensePackage, isWritable: true, isSigner: false },
+          { pubkey: user.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers(signers(program, [reviewer]))
+        .rpc();
+    } catch (e) {
+      payError = e;
+    }
+
+    expect(withdrawError?.error?.errorCode?.code).to.equal(
+      "ExpenseManagerPaused"
+    );
+    expect(payError?.error?.errorCode?.code).to.equal("ExpenseManagerPaused");
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.state).to.eql({ approved: {} });
+  });
+  it("grants a new member access through a proposal", async () => {
+    const { user, squad, squadMint, expenseManager, auditLog } = sharedData;
+    const member = anchor.web3.Keypair.generate().publicKey;
+    const { proposal } = await createAccessProposal(
+      program,
+      user,
+      member,
+      squad,
+      5
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [accessRecord] = getAccessRecordAddressAndBump(
+      program.programId,
+      expenseManager,
+      member
+    );
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    await program.methods
+      .squadsExecuteAccessProposal()
+      .accounts({
+        proposal,
+        accessRecord,
+        expenseManager,
+        auditLog,
+        squad,
+        squadMint,
+        proposalExecution,
+        member,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const accessRecordData = await program.account.accessRecord.fetch(
+      accessRecord
+    );
+    expect(accessRecordData.role).to.eql({ reviewer: {} });
+  });
+  it("pays approved packages once the manager is unpaused", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+      accessRecord,
+    } = sharedData;
+    const { proposal } = await createUnpauseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      6
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    await program.methods
+      .squadsExecuteUnpauseProposal()
+      .accounts({
+        proposal,
+        proposalExecution,
+        expenseManager,
+        squad,
+        squadMint,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const expensePackages = [2, 3].map(
+      (nonce) =>
+        getExpensePackageAddressAndBump(
+          expenseManager,
+          user.publicKey,
+          nonce,
+          program.programId
+        )[0]
+    );
+    await program.methods
+      .payExpensePackages([2, 3])
+      .accounts({
+        expenseManager,
+        auditLog,
+        managerStats,
+        accessRecord,
+        squad,
+        authority: reviewer.publicKey,
+      })
+      .remainingAccounts(
+        expensePackages.flatMap((expensePackage) => [
+          { pubkey: expensePackage, isWritable: true, isSigner: false },
+          { pubkey: user.publicKey, isWritable: true, isSigner: false },
+        ])
+      )
+      .signers(signers(program, [reviewer]))
+      .rpc();
+
+    for (const expensePackage of expensePackages) {
+      const expensePackageData = await program.account.expensePackage.fetch(
+        expensePackage
+      );
+      expect(expensePackageData.state).to.eql({ paid: {} });
+    }
+  });
+  it("keeps execution records within the retention period", async () => {
+    const { user, squad, squadSol, expenseManager } = sharedData;
+    const proposal = getProposalAddress(squad, 1);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    let error = null;
+    try {
+      await program.methods
+        .squadsCloseProposalExecution(1)
+        .accounts({
+          proposalExecution,
+          proposal,
+          expenseManager,
+          squad,
+          squadTreasury: squadSol,
+          rentReceiver: user.publicKey,
+        })
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    // the access proposal still exists in Squads and was executed moments ago
+    expect(error?.error?.errorCode?.code).to.equal("ProposalExecutionRetained");
+    expect(await connection.getAccountInfo(proposalExecution)).to.not.be.null;
+  });
+  it("closes a settled manager and its companion accounts", async () => {
+    const { user, squad, squadSol, squadMint, memberEquityRecord } = sharedData;
+    const {
+      expenseManagerPDA: expenseManager,
+      auditLogPDA: auditLog,
+      managerStatsPDA: managerStats,
+    } = await createExpenseManager(
+      program,
+      squadMint,
+      user,
+      "SQUADSCLOSETESTMANAGER"
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    await program.methods
+      .squadsInitializeExpenseManager(SQUADS_PROGRAM_ID)
+      .accounts({
+        expenseManager,
+        memberEquity: memberEquityRecord,
+        squad,
+        proposalWatermark,
+        member: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    await program.methods
+      .initializeManagerMetadata()
+      .accounts({
+        managerMetadata,
+        expenseManager,
+        payer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const { proposal } = await createCloseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      7
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    const treasuryBalancePre = await getBalance(connection, squadSol);
+    await program.methods
+      .squadsExecuteCloseProposal([])
+      .accounts({
+        proposal,
+        proposalExecution,
+        expenseManager,
+        auditLog,
+        managerStats,
+        managerMetadata,
+        squad,
+        squadMint,
+        squadTreasury: squadSol,
+        proposalWatermark,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const treasuryBalancePost = await getBalance(connection, squadSol);
+
+    for (const account of [
+      expenseManager,
+      auditLog,
+      managerStats,
+      managerMetadata,
+    ]) {
+      expect(await connection.getAccountInfo(account)).to.be.null;
+    }
+    expect(treasuryBalancePost).to.be.greaterThan(treasuryBalancePre);
+    const proposalWatermarkData =
+      await program.account.proposalWatermark.fetch(proposalWatermark);
+    expect(proposalWatermarkData.minProposalIndex).to.equal(
+      expenseManagerData.minProposalIndex
+    );
+  });
+  it("re-creates a closed manager under its old watermark", async () => {
+    const { user, squad, squadSol, squadMint, memberEquityRecord } = sharedData;
+    const {
+      expenseManagerPDA: expenseManager,
+      auditLogPDA: auditLog,
+      managerStatsPDA: managerStats,
+    } = await createExpenseManager(
+      program,
+      squadMint,
+      user,
+      "SQUADSCLOSETESTMANAGER"
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    await program.methods
+      .squadsInitializeExpenseManager(SQUADS_PROGRAM_ID)
+      .accounts({
+        expenseManager,
+        memberEquity: memberEquityRecord,
+        squad,
+        proposalWatermark,
+        member: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    const proposalWatermarkData =
+      await program.account.proposalWatermark.fetch(proposalWatermark);
+    expect(expenseManagerData.minProposalIndex).to.equal(
+      proposalWatermarkData.minProposalIndex
+    );
+
+    // the old manager's close proposal can't be executed against the new one
+    const proposal = getProposalAddress(squad, 7);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    let error = null;
+    try {
+      await program.methods
+        .squadsExecuteCloseProposal([])
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          auditLog,
+          managerStats,
+          managerMetadata,
+          squad,
+          squadMint,
+          squadTreasury: squadSol,
+          proposalWatermark,
+          signer: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error).to.not.be.null;
+    expect(await connection.getAccountInfo(expenseManager)).to.not.be.null;
+  });
+  it("refuses to close a manager with a queued withdrawal", async () => {
+    const {
+      user,
+      squad,
+      squadSol,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const { proposal: withdrawalProposal } = await createWithdrawalProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      8,
+      LAMPORTS_PER_SOL / 10
+    );
+    await castVoteOnProposal(program, user, squad, withdrawalProposal, 0);
+    const [withdrawalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      withdrawalProposal
+    );
+    const [withdrawalRequest] = anchor.utils.publicKey.findProgramAddressSync(
+      [
+        Buffer.from("withdrawal-request"),
+        expenseManager.toBuffer(),
+        new BN(8).toArrayLike(Buffer, "le", 4),
+      ],
+      program.programId
+    );
+    await program.methods
+      .squadsQueueWithdrawalProposal()
+      .accounts({
+        proposal: withdrawalProposal,
+        proposalExecution: withdrawalExecution,
+        withdrawalRequest,
+        expenseManager,
+        squad,
+        squadMint,
+        squadTreasury: squadSol,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const { proposal } = await createCloseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      9
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    let error = null;
+    try {
+      await program.methods
+        .squadsExecuteCloseProposal([])
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          auditLog,
+          managerStats,
+          managerMetadata,
+          squad,
+          squadMint,
+          squadTreasury: squadSol,
+          proposalWatermark,
+          signer: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error?.error?.errorCode?.code).to.equal(
+      "OutstandingWithdrawalRequests"
+    );
+
+    // with no timelock the request can be executed straight away
+    await program.methods
+      .executeWithdrawalRequest()
+      .accounts({
+        withdrawalRequest,
+        expenseManager,
+        auditLog,
+        managerStats,
+        destination: squadSol,
+        payer: user.publicKey,
+        executor: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    expect(expenseManagerData.withdrawalRequestCount).to.equal(0);
+  });
+  it("only lowers the withdrawal timelock once it has passed", async () => {
+    const { user, squad, squadMint, expenseManager } = sharedData;
+    const executeTimelockProposal = async (
+      seconds: number | null,
+      nonce: number
+    ) => {
+      const { proposal } = await createManagerSecondsProposal(
+        program,
+        user,
+        squad,
+        expenseManager,
+        "withdrawal timelock",
+        seconds,
+        nonce
+      );
+      await castVoteOnProposal(program, user, squad, proposal, 0);
+      const [proposalExecution] = getProposalExecutionAddressAndBump(
+        program.programId,
+        expenseManager,
+        proposal
+      );
+      await program.methods
+        .squadsExecuteWithdrawalTimelockProposal()
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          squad,
+          squadMint,
+          signer: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    };
+    const timelock = 60 * 60;
+    await executeTimelockProposal(timelock, 10);
+    await executeTimelockProposal(null, 11);
+
+    let error = null;
+    try {
+      await program.methods
+        .applyWithdrawalTimelock()
+        .accounts({ expenseManager })
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error?.error?.errorCode?.code).to.equal("TimelockChangeNotReady");
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    expect(expenseManagerData.withdrawalTimelock).to.equal(timelock);
+    expect(expenseManagerData.pendingWithdrawalTimelock).to.equal(0);
+  });
+  it("refuses to close a timelocked manager", async () => {
+    const {
+      user,
+      squad,
+      squadSol,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const { proposal } = await createCloseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      12
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    let error = null;
+    try {
+      await program.methods
+        .squadsExecuteCloseProposal([])
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          auditLog,
+          managerStats,
+          managerMetadata,
+          squad,
+          squadMint,
+          squadTreasury: squadSol,
+          proposalWatermark,
+          signer: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error?.error?.errorCode?.code).to.equal("WithdrawalTimelocked");
+    expect(await connection.getAccountInfo(expenseManager)).to.not.be.null;
+  });
+  it("sweeps approved packages past the deadline set at approval", async () => {
+    const { user, expenseManager, auditLog, managerStats } = sharedData;
+    const quantity = toBN(200_000);
+    // approved under a day-long deadline which is shortened afterwards
+    await setWithdrawalDeadline(program, sharedData, 24 * 60 * 60);
+    await submitExpensePackage(program, sharedData, 4, quantity);
+    const { expensePackage: protectedPackage } = await approveExpensePackage(
+      program,
+      sharedData,
+      4
+    );
+    await setWithdrawalDeadline(program, sharedData, 0);
+    await submitExpensePackage(program, sharedData, 5, quantity);
+    const { expensePackage } = await approveExpensePackage(
+      program,
+      sharedData,
+      5
+    );
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    const managerBalancePre = await getBalance(connection, expenseManager);
+    const managerStatsPre = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    await program.methods
+      .crankExpensePackage(5)
+      .accounts({
+        expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        cranker: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    let error = null;
+    try {
+      await program.methods
+        .crankExpensePackage(4)
+        .accounts({
+          expensePackage: protectedPackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          cranker: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    const managerBalancePost = await getBalance(connection, expenseManager);
+    const managerStatsPost = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.state).to.eql({ expired: {} });
+    expect(managerBalancePost - managerBalancePre).to.equal(
+      quantity.toNumber()
+    );
+    const approvedReversed = managerStatsPre.totalApproved.sub(
+      managerStatsPost.totalApproved
+    );
+    expect(approvedReversed.toNumber()).to.equal(quantity.toNumber());
+    expect(error?.error?.errorCode?.code).to.equal("PackageNotOverdue");
+  });
+  it("expires pending packages past the maximum age", async () => {
+    const { user, expenseManager, auditLog, managerStats } = sharedData;
+    const [adminAccessRecord] = getAccessRecordAddressAndBump(
+      program.programId,
+      expenseManager,
+      user.publicKey
+    );
+    await program.methods
+      .setPackageDeadlines(null, 0, null)
+      .accounts({
+        expenseManager,
+        accessRecord: adminAccessRecord,
+        authority: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      6,
+      toBN(100_000)
+    );
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await program.methods
+      .crankExpensePackage(6)
+      .accounts({
+        expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        cranker: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.state).to.eql({ expired: {} });
+    const managerStatsData = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    expect(managerStatsData.pendingPackages.toNumber()).to.equal(0);
+  });
+  it("pays a backdated recurring expense from its approval", async () => {
+    const {
+      user,
+      squad,
+      squadMint,
+      expenseManager,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const payee = anchor.web3.Keypair.generate().publicKey;
+    const amount = toBN(LAMPORTS_PER_SOL / 100);
+    const period = 60 * 60;
+    const start = Math.floor(Date.now() / 1000) - 10 * period;
+    const [recurringExpense] = getRecurringExpenseAddressAndBump(
+      expenseManager,
+      user.publicKey,
+      0,
+      program.programId
+    );
+    await program.methods
+      .createRecurringExpense(
+        0,
+        "SQUADSTESTRECURRING",
+        amount,
+        period,
+        toBN(start),
+        null,
+        amount.muln(12),
+        null,
+        payee
+      )
+      .accounts({
+        recurringExpense,
+        expenseManager,
+        owner: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const { proposal } = await createRecurringExpenseProposal(
+      program,
+      user,
+      squad,
+      recurringExpense,
+      13
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    await program.methods
+      .squadsExecuteRecurringExpenseProposal()
+      .accounts({
+        proposal,
+        proposalExecution,
+        recurringExpense,
+        expenseManager,
+        squad,
+        squadMint,
+        signer: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const payRecurringExpense = (payoutSquad: PublicKey) =>
+      program.methods
+        .payRecurringExpense()
+        .accounts({
+          recurringExpense,
+          expenseManager,
+          auditLog,
+          managerStats,
+          payee,
+          squad: payoutSquad,
+          cranker: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    let squadError = null;
+    try {
+      await payRecurringExpense(anchor.web3.Keypair.generate().publicKey);
+    } catch (e) {
+      squadError = e;
+    }
+    await payRecurringExpense(squad);
+    // the periods before approval are skipped, so the next payout isn't due
+    let error = null;
+    try {
+      await payRecurringExpense(squad);
+    } catch (e) {
+      error = e;
+    }
+
+    const recurringExpenseData = await program.account.recurringExpense.fetch(
+      recurringExpense
+    );
+    expect(recurringExpenseData.approvedBy.toString()).to.equal(
+      squad.toString()
+    );
+    expect(recurringExpenseData.totalPaid.toNumber()).to.equal(
+      amount.toNumber()
+    );
+    expect(recurringExpenseData.nextPayoutAt.toNumber()).to.be.greaterThan(
+      start + 10 * period
+    );
+    expect(await getBalance(connection, payee)).to.equal(amount.toNumber());
+    expect(error?.error?.errorCode?.code).to.equal("RecurringExpenseNotDue");
+    // the squad's emergency lock is read from the manager's own squad only
+    expect(squadError?.error?.errorCode?.code).to.equal("SquadMismatch");
+  });
+  it("rejects recurring expenses without an end or a cap", async () => {
+    const { user, expenseManager } = sharedData;
+    const [recurringExpense] = getRecurringExpenseAddressAndBump(
+      expenseManager,
+      user.publicKey,
+      1,
+      program.programId
+    );
+    let error = null;
+    try {
+      await program.methods
+        .createRecurringExpense(
+          1,
+          "SQUADSTESTUNBOUNDED",
+          toBN(LAMPORTS_PER_SOL / 100),
+          60 * 60,
+          toBN(Math.floor(Date.now() / 1000)),
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          recurringExpense,
+          expenseManager,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error?.error?.errorCode?.code).to.equal("RecurringExpenseUnbounded");
+    expect(await connection.getAccountInfo(recurringExpense)).to.be.null;
+  });
+  it("returns an advance refund to the manager's budget", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      expenseManager,
+      accessRecord,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const quantity = toBN(300_000);
+    const actualAmount = toBN(200_000);
+    const refund = quantity.sub(actualAmount).toNumber();
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      7,
+      quantity,
+      true
+    );
+    await approveExpensePackage(program, sharedData, 7);
+    await program.methods
+      .withdrawFromExpensePackage(7)
+      .accounts({
+        expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        payee: user.publicKey,
+        squad,
+        owner: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const reconcileExpenseAdvance = () =>
+      program.methods
+        .reconcileExpenseAdvance(7, actualAmount)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          managerStats,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    await reconcileExpenseAdvance();
+    await program.methods
+      .denyExpenseAdvance(7)
+      .accounts({
+        expensePackage,
+        expenseManager,
+        auditLog,
+        managerStats,
+        accessRecord,
+        payee: user.publicKey,
+        owner: user.publicKey,
+        authority: reviewer.publicKey,
+      })
+      .signers(signers(program, [reviewer]))
+      .rpc();
+    const auditLogData = await program.account.auditLog.fetch(auditLog);
+    const newest = auditLogData.count.toNumber() - 1;
+    const denial = auditLogData.entries[newest % 64];
+    expect(denial.action).to.equal(11);
+    assert(denial.subject.equals(expensePackage));
+    await reconcileExpenseAdvance();
+
+    const managerBalancePre = await getBalance(connection, expenseManager);
+    const managerStatsPre = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    const approveExpenseAdvance = () =>
+      program.methods
+        .approveExpenseAdvance(7)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          accessRecord,
+          payee: user.publicKey,
+          owner: user.publicKey,
+          authority: reviewer.publicKey,
+        })
+        .signers(signers(program, [reviewer]))
+        .rpc();
+    await approveExpenseAdvance();
+    let error = null;
+    try {
+      await approveExpenseAdvance();
+    } catch (e) {
+      error = e;
+    }
+
+    const managerBalancePost = await getBalance(connection, expenseManager);
+    const managerStatsPost = await program.account.expenseManagerStats.fetch(
+      managerStats
+    );
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.state).to.eql({ reconciled: {} });
+    expect(managerBalancePost - managerBalancePre).to.equal(refund);
+    const approvedReversed = managerStatsPre.totalApproved.sub(
+      managerStatsPost.totalApproved
+    );
+    const paidReversed = managerStatsPre.totalPaid.sub(
+      managerStatsPost.totalPaid
+    );
+    expect(approvedReversed.toNumber()).to.equal(refund);
+    expect(paidReversed.toNumber()).to.equal(refund);
+    expect(managerStatsPost.unreconciledAdvances.toNumber()).to.equal(0);
+    expect(error?.error?.errorCode?.code).to.equal("PackageNotReconciling");
+  });
+  it("moves only the approved amount out of the manager", async () => {
+    const { expenseManager } = sharedData;
+    const quantity = toBN(300_000);
+    const approvedAmount = toBN(120_000);
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      8,
+      quantity
+    );
+    let error = null;
+    try {
+      await approveExpensePackage(program, sharedData, 8, quantity.addn(1));
+    } catch (e) {
+      error = e;
+    }
+
+    const managerBalancePre = await getBalance(connection, expenseManager);
+    const packageBalancePre = await getBalance(connection, expensePackage);
+    await approveExpensePackage(program, sharedData, 8, approvedAmount);
+    const managerBalancePost = await getBalance(connection, expenseManager);
+    const packageBalancePost = await getBalance(connection, expensePackage);
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(error?.error?.errorCode?.code).to.equal("InvalidApprovedAmount");
+    expect(expensePackageData.state).to.eql({ approved: {} });
+    expect(expensePackageData.quantity.toNumber()).to.equal(
+      quantity.toNumber()
+    );
+    expect(expensePackageData.approvedAmount.toNumber()).to.equal(
+      approvedAmount.toNumber()
+    );
+    expect(managerBalancePre - managerBalancePost).to.equal(
+      approvedAmount.toNumber()
+    );
+    expect(packageBalancePost - packageBalancePre).to.equal(
+      approvedAmount.toNumber()
+    );
+  });
+  it("releases a vesting package over several withdrawals", async () => {
+    const { user, squad, expenseManager, auditLog, managerStats } = sharedData;
+    const quantity = toBN(300_000);
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      9,
+      quantity
+    );
+    const now = await connection.getBlockTime(await connection.getSlot());
+    await approveExpensePackage(program, sharedData, 9, null, {
+      start: toBN(now),
+      cliff: toBN(now + 3),
+      end: toBN(now + 12),
+    });
+    const withdraw = () =>
+      program.methods
+        .withdrawFromExpensePackage(9)
+        .accounts({
+          expensePackage,
+          expenseManager,
+          auditLog,
+          managerStats,
+          payee: user.publicKey,
+          squad,
+          owner: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+
+    let error = null;
+    try {
+      await withdraw();
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal("NothingVested");
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    await withdraw();
+    let expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    const firstRelease = expensePackageData.withdrawnAmount.toNumber();
+    expect(firstRelease).to.be.greaterThan(0);
+    expect(firstRelease).to.be.lessThan(quantity.toNumber());
+    expect(expensePackageData.state).to.eql({ approved: {} });
+
+    await new Promise((resolve) => setTimeout(resolve, 10000));
+    const userBalancePre = await getBalance(connection, user.publicKey);
+    await withdraw();
+    const userBalancePost = await getBalance(connection, user.publicKey);
+    expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.withdrawnAmount.toNumber()).to.equal(
+      quantity.toNumber()
+    );
+    expect(expensePackageData.state).to.eql({ paid: {} });
+    expect(userBalancePost - userBalancePre).to.equal(
+      quantity.toNumber() - firstRelease
+    );
+  });
+  it("rejects approvals over a category's budget", async () => {
+    const { expenseManager } = sharedData;
+    await addExpenseCategory(program, sharedData, "travel", toBN(250_000), []);
+    await submitExpensePackage(
+      program,
+      sharedData,
+      10,
+      toBN(200_000),
+      false,
+      0
+    );
+    await approveExpensePackage(program, sharedData, 10);
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      11,
+      toBN(100_000),
+      false,
+      0
+    );
+    let error = null;
+    try {
+      await approveExpensePackage(program, sharedData, 11);
+    } catch (e) {
+      error = e;
+    }
+
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(error?.error?.errorCode?.code).to.equal("CategoryBudgetExceeded");
+    expect(expenseManagerData.categories[0].spent.toNumber()).to.equal(
+      200_000
+    );
+    expect(expensePackageData.state).to.eql({ pending: {} });
+  });
+  it("only lets a category's reviewers approve its packages", async () => {
+    const { user, reviewer, expenseManager } = sharedData;
+    const [adminAccessRecord] = getAccessRecordAddressAndBump(
+      program.programId,
+      expenseManager,
+      user.publicKey
+    );
+    const otherReviewer = anchor.web3.Keypair.generate().publicKey;
+    await addExpenseCategory(program, sharedData, "equipment", null, [
+      otherReviewer,
+    ]);
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      12,
+      toBN(100_000),
+      false,
+      1
+    );
+    let error = null;
+    try {
+      await approveExpensePackage(program, sharedData, 12);
+    } catch (e) {
+      error = e;
+    }
+    await program.methods
+      .updateExpenseCategory(1, "equipment", null, [
+        otherReviewer,
+        reviewer.publicKey,
+      ])
+      .accounts({
+        expenseManager,
+        accessRecord: adminAccessRecord,
+        authority: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    await approveExpensePackage(program, sharedData, 12);
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(error?.error?.errorCode?.code).to.equal("UserNotCategoryReviewer");
+    expect(expensePackageData.state).to.eql({ approved: {} });
+  });
+  it("pays the payee directly on approval", async () => {
+    const { user, expenseManager } = sharedData;
+    const quantity = toBN(100_000);
+    await setDirectPayout(program, sharedData, true);
+    const { expensePackage } = await submitExpensePackage(
+      program,
+      sharedData,
+      13,
+      quantity
+    );
+
+    const userBalancePre = await getBalance(connection, user.publicKey);
+    const managerBalancePre = await getBalance(connection, expenseManager);
+    await approveExpensePackage(program, sharedData, 13);
+    const userBalancePost = await getBalance(connection, user.publicKey);
+    const managerBalancePost = await getBalance(connection, expenseManager);
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(expensePackageData.state).to.eql({ paid: {} });
+    expect(expensePackageData.withdrawnAmount.toNumber()).to.equal(
+      quantity.toNumber()
+    );
+    expect(userBalancePost - userBalancePre).to.equal(quantity.toNumber());
+    expect(managerBalancePre - managerBalancePost).to.equal(
+      quantity.toNumber()
+    );
+  });
+  it("pays payees directly on batch approval", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      reviewerMemberEquityRecord,
+      expenseManager,
+      accessRecord,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const quantity = toBN(100_000);
+    const nonces = [14, 15];
+    const expensePackages = [];
+    for (const nonce of nonces) {
+      const { expensePackage } = await submitExpensePackage(
+        program,
+        sharedData,
+        nonce,
+        quantity
+      );
+      expensePackages.push(expensePackage);
+    }
+
+    const userBalancePre = await getBalance(connection, user.publicKey);
+    await program.methods
+      .squadsApproveExpensePackages(nonces, nonces.map(() => null))
+      .accounts({
+        expenseManager,
+        auditLog,
+        managerStats,
+        accessRecord,
+        memberEquity: reviewerMemberEquityRecord,
+        squad,
+        authority: reviewer.publicKey,
+      })
+      .remainingAccounts(
+        expensePackages.flatMap((expensePackage) => [
+          { pubkey: expensePackage, isWritable: true, isSigner: false },
+          { pubkey: user.publicKey, isWritable: true, isSigner: false },
+        ])
+      )
+      .signers(signers(program, [reviewer]))
+      .rpc();
+    const userBalancePost = await getBalance(connection, user.publicKey);
+    await setDirectPayout(program, sharedData, false);
+
+    for (const expensePackage of expensePackages) {
+      const expensePackageData = await program.account.expensePackage.fetch(
+        expensePackage
+      );
+      expect(expensePackageData.state).to.eql({ paid: {} });
+    }
+    expect(userBalancePost - userBalancePre).to.equal(
+      quantity.toNumber() * nonces.length
+    );
+  });
+  it("approves part of a package in a batch", async () => {
+    const {
+      user,
+      reviewer,
+      squad,
+      reviewerMemberEquityRecord,
+      expenseManager,
+      accessRecord,
+      auditLog,
+      managerStats,
+    } = sharedData;
+    const quantity = toBN(100_000);
+    const nonces = [16, 17];
+    const approvedAmounts = [null, toBN(40_000)];
+    const expensePackages = [];
+    for (const nonce of nonces) {
+      const { expensePackage } = await submitExpensePackage(
+        program,
+        sharedData,
+        nonce,
+        quantity
+      );
+      expensePackages.push(expensePackage);
+    }
+    const approveExpensePackages = (amounts: (BN | null)[]) =>
+      program.methods
+        .squadsApproveExpensePackages(nonces, amounts)
+        .accounts({
+          expenseManager,
+          auditLog,
+          managerStats,
+          accessRecord,
+          memberEquity: reviewerMemberEquityRecord,
+          squad,
+          authority: reviewer.publicKey,
+        })
+        .remainingAccounts(
+          expensePackages.flatMap((expensePackage) => [
+            { pubkey: expensePackage, isWritable: true, isSigner: false },
+            { pubkey: user.publicKey, isWritable: true, isSigner: false },
+          ])
+        )
+        .signers(signers(program, [reviewer]))
+        .rpc();
+    let error = null;
+    try {
+      await approveExpensePackages(approvedAmounts.slice(1));
+    } catch (e) {
+      error = e;
+    }
+
+    const managerBalancePre = await getBalance(connection, expenseManager);
+    await approveExpensePackages(approvedAmounts);
+    const managerBalancePost = await getBalance(connection, expenseManager);
+
+    const [fullPackageData, partialPackageData] = await Promise.all(
+      expensePackages.map((expensePackage) =>
+        program.account.expensePackage.fetch(expensePackage)
+      )
+    );
+    expect(error?.error?.errorCode?.code).to.equal("BatchAccountsMismatch");
+    expect(fullPackageData.state).to.eql({ approved: {} });
+    expect(fullPackageData.approvedAmount).to.equal(null);
+    expect(partialPackageData.state).to.eql({ approved: {} });
+    expect(partialPackageData.approvedAmount.toNumber()).to.equal(40_000);
+    expect(managerBalancePre - managerBalancePost).to.equal(140_000);
+  });
+  it("denies draft packages when closing a manager", async () => {
+    const { user, squad, squadSol, squadMint, memberEquityRecord } = sharedData;
+    const {
+      expenseManagerPDA: expenseManager,
+      auditLogPDA: auditLog,
+      managerStatsPDA: managerStats,
+    } = await createExpenseManager(
+      program,
+      squadMint,
+      user,
+      "SQUADSDRAFTCLOSEMANAGER"
+    );
+    const [proposalWatermark] = getProposalWatermarkAddressAndBump(
+      expenseManager,
+      squad,
+      program.programId
+    );
+    await program.methods
+      .squadsInitializeExpenseManager(SQUADS_PROGRAM_ID)
+      .accounts({
+        expenseManager,
+        memberEquity: memberEquityRecord,
+        squad,
+        proposalWatermark,
+        member: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+    const nonce = 18;
+    const [expensePackage] = getExpensePackageAddressAndBump(
+      expenseManager,
+      user.publicKey,
+      nonce,
+      program.programId
+    );
+    const [userData] = getUserDataAddressAndBump(
+      user.publicKey,
+      program.programId
+    );
+    await program.methods
+      .squadsCreateExpensePackage(nonce, "DRAFT", "", toBN(100_000), null)
+      .accounts({
+        expensePackage,
+        userData,
+        expenseManager,
+        managerStats,
+        memberEquity: memberEquityRecord,
+        squad,
+        owner: user.publicKey,
+      })
+      .signers(signers(program, [user]))
+      .rpc();
+
+    const { proposal } = await createCloseProposal(
+      program,
+      user,
+      squad,
+      expenseManager,
+      14
+    );
+    await castVoteOnProposal(program, user, squad, proposal, 0);
+    const [proposalExecution] = getProposalExecutionAddressAndBump(
+      program.programId,
+      expenseManager,
+      proposal
+    );
+    const [managerMetadata] = getManagerMetadataAddressAndBump(
+      expenseManager,
+      program.programId
+    );
+    const closeExpenseManager = (forceDenyNonces: number[]) =>
+      program.methods
+        .squadsExecuteCloseProposal(forceDenyNonces)
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          auditLog,
+          managerStats,
+          managerMetadata,
+          squad,
+          squadMint,
+          squadTreasury: squadSol,
+          proposalWatermark,
+          signer: user.publicKey,
+        })
+        .remainingAccounts(
+          forceDenyNonces.map(() => ({
+            pubkey: expensePackage,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers(signers(program, [user]))
+        .rpc();
+    let error = null;
+    try {
+      await closeExpenseManager([]);
+    } catch (e) {
+      error = e;
+    }
+    await closeExpenseManager([nonce]);
+
+    const expensePackageData = await program.account.expensePackage.fetch(
+      expensePackage
+    );
+    expect(error?.error?.errorCode?.code).to.equal(
+      "OutstandingExpensePackages"
+    );
+    expect(expensePackageData.state).to.eql({ denied: {} });
+    expect(await connection.getAccountInfo(expenseManager)).to.be.null;
+  });
+  it("sets the execution window through a proposal", async () => {
+    const { user, squad, squadMint, expenseManager } = sharedData;
+    const executeExecutionWindowProposal = async (
+      seconds: number,
+      nonce: number
+    ) => {
+      const { proposal } = await createManagerSecondsProposal(
+        program,
+        user,
+        squad,
+        expenseManager,
+        "execution window",
+        seconds,
+        nonce
+      );
+      await castVoteOnProposal(program, user, squad, proposal, 0);
+      const [proposalExecution] = getProposalExecutionAddressAndBump(
+        program.programId,
+        expenseManager,
+        proposal
+      );
+      await program.methods
+        .squadsExecuteExecutionWindowProposal()
+        .accounts({
+          proposal,
+          proposalExecution,
+          expenseManager,
+          squad,
+          squadMint,
+          signer: user.publicKey,
+        })
+        .signers(signers(program, [user]))
+        .rpc();
+    };
+    let error = null;
+    try {
+      await executeExecutionWindowProposal(60, 15);
+    } catch (e) {
+      error = e;
+    }
+    const executionWindow = 24 * 60 * 60;
+    await executeExecutionWindowProposal(executionWindow, 16);
+
+    const expenseManagerData = await program.account.expenseManager.fetch(
+      expenseManager
+    );
+    expect(error?.error?.errorCode?.code).to.equal("ExecutionWindowTooShort");
+    expect(expenseManagerData.proposalExecutionWindow).to.equal(
+      executionWindow
+    );
+  });
 });
@@ -94,12 +94,76 @@ export function getUserDataAddressAndBump(
   );
 }
 
+// namespace is the creator, realm or squad; null looks up a legacy name-only manager
 export function getExpenseManagerAddressAndBump(
+  namespace: PublicKey | null,
   name: string,
   programId: PublicKey
+): [PublicKey, number] {
+  const seeds = namespace
+    ? [Buffer.from("expense-manager"), namespace.toBuffer(), Buffer.from(name)]
+    : [Buffer.from("expense-manager"), Buffer.from(name)];
+  return anchor.utils.publicKey.findProgramAddressSync(seeds, programId);
+}
+
+export function getAuditLogAddressAndBump(
+  expenseManager: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return anchor.utils.publicKey.findProgramAddressSync(
+    [Buffer.from("audit-log"), expenseManager.toBuffer()],
+    programId
+  );
+}
+
+export function getManagerStatsAddressAndBump(
+  expenseManager: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return anchor.utils.publicKey.findProgramAddressSync(
+    [Buffer.from("manager-stats"), expenseManager.toBuffer()],
+    programId
+  );
+}
+
+export function getManagerMetadataAddressAndBump(
+  expenseManager: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return anchor.utils.publicKey.findProgramAddressSync(
+    [Buffer.from("manager-metadata"), expenseManager.toBuffer()],
+    programId
+  );
+}
+
+export function getProposalWatermarkAddressAndBump(
+  expenseManager: PublicKey,
+  squad: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return anchor.utils.publicKey.findProgramAddressSync(
+    [
+      Buffer.from("proposal-watermark"),
+      expenseManager.toBuffer(),
+      squad.toBuffer(),
+    ],
+    programId
+  );
+}
+
+export function getRecurringExpenseAddressAndBump(
+  expenseManager: PublicKey,
+  owner: PublicKey,
+  nonce: number,
+  programId: PublicKey
 ): [PublicKey, number] {
   return anchor.utils.publicKey.findProgramAddressSync(
-    [Buffer.from("expense-manager"), Buffer.from(name)],
+    [
+      Buffer.from("recurring-expense"),
+      expenseManager.toBuffer(),
+      owner.toBuffer(),
+      new BN(nonce).toArrayLike(Buffer, "le", 4),
+    ],
     programId
   );
 }